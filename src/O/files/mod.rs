@@ -1,7 +1,6 @@
 
 
-pub mod compression;
-use self::compression::{CompressionLevel, CompressionType};
+use crate::files::compression::{CompressionLevel, CompressionType};
 pub mod loading;
 
 
@@ -0,0 +1,324 @@
+use std::{collections::{HashMap, VecDeque}, fs, path::Path, sync::{mpsc::{channel, Receiver, Sender}, Arc, Mutex}, thread, time::{Duration, Instant}};
+
+use crate::{
+	files::*,
+	font::*,
+	image::*,
+	model::*,
+	sound::*,
+	texture::*,
+};
+
+
+/// #### Source
+/// Where the data of an asset is read from.
+#[derive(Debug, Clone)]
+pub enum Source {
+	File(String),
+	Archive(Arc<dyn Archive + Send + Sync>, String),
+}
+impl Source {
+	
+	/// #### read
+	/// Reads and decompresses the data.
+	fn read(&self) -> Result<Vec<u8>, BulkError> {
+		match self {
+			Source::File(filename) => fs::read(filename).map_err(|_| BulkError::FailedToOpen),
+			Source::Archive(archive, name) => archive.read(name).map(|data| data.into_owned()),
+		}
+	}
+	/// #### file_type
+	/// Returns the extension Raylib's memory loaders expect for the data.
+	/// 
+	/// Files use their own extension, archive entries are guessed from their header.
+	/// Empty if it can't be told, which Raylib's loaders refuse.
+	fn file_type(&self, data: &[u8]) -> String {
+		match self {
+			Source::File(filename) => match Path::new(filename).extension() {
+				Some(ext) => format!(".{}", ext.to_string_lossy()),
+				None => extension(data).unwrap_or_default().to_string(),
+			},
+			Source::Archive(..) => extension(data).unwrap_or_default().to_string(),
+		}
+	}
+	
+}
+
+/// #### AssetKind
+/// What an asset is turned into once its data has been read.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AssetKind {
+	Texture,
+	/// Font of the input size.
+	Font(i32),
+	Sound,
+	Music,
+	Model,
+	/// Data left as it is read.
+	Data,
+}
+
+/// #### Asset
+/// Asset finished by the Loader.
+#[derive(Debug, Clone)]
+pub enum Asset {
+	Texture(TextureRl),
+	Font(Font),
+	Sound(Sound),
+	Music(Music),
+	Model(ModelRl),
+	Data(Vec<u8>),
+}
+
+/// #### Job
+/// Asset waiting to be read by a worker.
+#[derive(Debug, Clone)]
+struct Job {
+	name: String,
+	source: Source,
+	kind: AssetKind,
+}
+
+/// #### Decoded
+/// Data decoded by a worker, waiting to be uploaded on the main thread.
+#[derive(Debug, Clone)]
+enum Decoded {
+	Image(Image),
+	Font(FontAtlas),
+	Wave(Wave),
+	/// Data Raylib can only load on the main thread, with its file type.
+	Raw(String, Vec<u8>),
+}
+/// Decoded data belongs to whichever thread holds it, Raylib keeps no references to it.
+unsafe impl Send for Decoded {}
+
+/// #### Read
+/// Data read and decoded by a worker, or None if that failed, waiting to be finished on the main thread.
+#[derive(Debug, Clone)]
+struct Read {
+	name: String,
+	kind: AssetKind,
+	data: Option<Decoded>,
+}
+
+/// #### Loader
+/// Loads assets in the background.
+/// 
+/// Worker threads read, decompress and decode the data, while only uploading to the GPU or the audio device happens on the main thread in update.
+/// Textures, Sounds and TTF or OTF Fonts are decoded on the workers. Music and Models are loaded whole in update, as Raylib only loads them at once.
+/// Each update only spends up to budget finishing assets, so loading screens keep drawing.
+#[derive(Debug, Clone)]
+pub struct Loader {
+	pub budget: Duration,
+	pub threads: usize,
+	
+	jobs: Option<Sender<Job>>,
+	reads: Option<Arc<Mutex<Receiver<Read>>>>,
+	waiting: VecDeque<Read>,
+	
+	ready: HashMap<String, Asset>,
+	failed: Vec<String>,
+	
+	queued: usize,
+	finished: usize,
+}
+impl Default for Loader {
+	fn default() -> Self {
+		Self {
+			budget: Duration::from_millis(4),
+			threads: thread::available_parallelism().map(|n| n.get().min(4)).unwrap_or(1),
+			jobs: None,
+			reads: None,
+			waiting: VecDeque::new(),
+			ready: HashMap::new(),
+			failed: Vec::new(),
+			queued: 0,
+			finished: 0,
+		}
+	}
+}
+impl Loader {
+	
+	/// #### new
+	/// Creates a Loader. Worker threads aren't started until the first asset is queued.
+	pub fn new() -> Self {
+		Self::default()
+	}
+	/// #### queue
+	/// Queues the asset to be loaded under the input name.
+	/// 
+	/// Queueing after everything has finished starts counting progress from zero again.
+	pub fn queue(&mut self, name: &str, source: Source, kind: AssetKind) -> &mut Self {
+		if self.done() {
+			self.queued = 0;
+			self.finished = 0;
+		}
+		
+		if self.jobs.is_none() { self.start(); }
+		
+		let job = Job { name: name.to_string(), source, kind };
+		match self.jobs.as_ref().unwrap().send(job) {
+			Ok(_) => self.queued += 1,
+			Err(_) => self.failed.push(name.to_string()),
+		}
+		
+		self
+	}
+	/// #### queue_file
+	/// Queues a file to be loaded.
+	pub fn queue_file(&mut self, name: &str, filename: &str, kind: AssetKind) -> &mut Self {
+		self.queue(name, Source::File(filename.to_string()), kind)
+	}
+	/// #### queue_archive
+	/// Queues an entry of an archive to be loaded.
+	pub fn queue_archive(&mut self, name: &str, archive: &Arc<dyn Archive + Send + Sync>, entry: &str, kind: AssetKind) -> &mut Self {
+		self.queue(name, Source::Archive(archive.clone(), entry.to_string()), kind)
+	}
+	
+	/// #### update
+	/// Finishes the assets read by the workers, until the budget for this frame runs out.
+	/// 
+	/// At least one asset is finished per update, however long it takes.
+	pub fn update(&mut self) -> &mut Self {
+		if let Some(reads) = self.reads.as_ref() {
+			if let Ok(reads) = reads.lock() {
+				self.waiting.extend(reads.try_iter());
+			}
+		}
+		
+		let start = Instant::now();
+		while let Some(read) = self.waiting.pop_front() {
+			match finish(read.kind, read.data) {
+				Some(asset) => { self.ready.insert(read.name, asset); }
+				None => self.failed.push(read.name),
+			}
+			self.finished += 1;
+			
+			if start.elapsed() >= self.budget { break }
+		}
+		
+		self
+	}
+	/// #### progress
+	/// Returns the fraction of queued assets that have finished, from 0.0 to 1.0.
+	pub fn progress(&self) -> f32 {
+		if self.queued == 0 { return 1.0 }
+		
+		self.finished as f32 / self.queued as f32
+	}
+	/// #### done
+	/// Checks whether every queued asset has finished.
+	pub fn done(&self) -> bool {
+		self.finished >= self.queued
+	}
+	/// #### get
+	/// Returns the asset if it has finished loading.
+	pub fn get(&self, name: &str) -> Option<&Asset> {
+		self.ready.get(name)
+	}
+	/// #### take
+	/// Removes the asset from the Loader if it has finished loading.
+	pub fn take(&mut self, name: &str) -> Option<Asset> {
+		self.ready.remove(name)
+	}
+	/// #### take_failed
+	/// Returns the names of the assets that failed to load since the last call.
+	pub fn take_failed(&mut self) -> Vec<String> {
+		std::mem::take(&mut self.failed)
+	}
+	
+	/// #### start
+	/// Spawns the worker threads.
+	fn start(&mut self) -> &mut Self {
+		let (job_sender, job_receiver) = channel::<Job>();
+		let (read_sender, read_receiver) = channel::<Read>();
+		let job_receiver = Arc::new(Mutex::new(job_receiver));
+		
+		for _ in 0..self.threads.max(1) {
+			let jobs = job_receiver.clone();
+			let reads = read_sender.clone();
+			thread::spawn(move || worker(jobs, reads));
+		}
+		
+		self.jobs = Some(job_sender);
+		self.reads = Some(Arc::new(Mutex::new(read_receiver)));
+		
+		self
+	}
+	
+}
+
+/// #### worker
+/// Reads and decodes the data of queued assets until the Loader is dropped.
+fn worker(jobs: Arc<Mutex<Receiver<Job>>>, reads: Sender<Read>) {
+	loop {
+		let job = match jobs.lock() {
+			Ok(jobs) => jobs.recv(),
+			Err(_) => return,
+		};
+		let Ok(job) = job else { return };
+		
+		let data = job.source.read().ok().and_then(|data| decode(job.kind, job.source.file_type(&data), data));
+		if reads.send(Read { name: job.name, kind: job.kind, data }).is_err() { return }
+	}
+}
+
+/// #### decode
+/// Decodes the data on a worker as far as it goes without the GPU or the audio device.
+fn decode(kind: AssetKind, file_type: String, mut data: Vec<u8>) -> Option<Decoded> {
+	match kind {
+		AssetKind::Texture => {
+			let image = Image::load_from_memory(&file_type, &mut data);
+			
+			if image.is_ready() { Some(Decoded::Image(image)) } else { None }
+		}
+		AssetKind::Font(size) if matches!(file_type.to_lowercase().as_str(), ".ttf" | ".otf") => {
+			Font::decode_from_memory(&data, size).map(Decoded::Font)
+		}
+		AssetKind::Sound => {
+			let wave = Wave::load_from_memory(&file_type, &data);
+			
+			if wave.ready() { Some(Decoded::Wave(wave)) } else { None }
+		}
+		_ => Some(Decoded::Raw(file_type, data)),
+	}
+}
+
+/// #### finish
+/// Turns the decoded data into an asset on the main thread, uploading it to the GPU or the audio device.
+fn finish(kind: AssetKind, data: Option<Decoded>) -> Option<Asset> {
+	match data? {
+		Decoded::Image(image) => {
+			let texture = image.texture();
+			image.unload();
+			
+			if texture.is_ready() { Some(Asset::Texture(texture)) } else { None }
+		}
+		Decoded::Font(atlas) => {
+			let font = Font::from_atlas(atlas);
+			
+			if font.ready() { Some(Asset::Font(font)) } else { None }
+		}
+		Decoded::Wave(mut wave) => {
+			let sound = wave.to_sound();
+			wave.unload();
+			
+			if sound.ready() { Some(Asset::Sound(sound)) } else { None }
+		}
+		Decoded::Raw(file_type, data) => match kind {
+			AssetKind::Font(size) => {
+				let font = Font::load_from_memory(&file_type, data, size, Vec::new());
+				
+				if font.ready() { Some(Asset::Font(font)) } else { None }
+			}
+			AssetKind::Music => Music::load_from_memory(&file_type, data).map(Asset::Music),
+			AssetKind::Model => {
+				let mut model = ModelRl::load_from_memory(&file_type, &data);
+				
+				if model.ready() { Some(Asset::Model(model)) } else { None }
+			}
+			AssetKind::Data => Some(Asset::Data(data)),
+			AssetKind::Texture | AssetKind::Sound => None,
+		},
+	}
+}
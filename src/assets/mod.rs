@@ -0,0 +1,420 @@
+use std::{cell::{Ref, RefCell, RefMut}, collections::HashMap, fmt::{self, Debug}, fs, rc::{Rc, Weak}, time::{Duration, Instant, SystemTime}};
+
+use crate::{
+	font::*,
+	model::*,
+	shader::*,
+	sound::*,
+	texture::*,
+};
+
+pub mod loader;
+
+
+/// #### Resource
+/// GPU or audio resource that can be held by Assets.
+pub trait Resource: Debug + Sized {
+	/// #### unload
+	/// Frees the resource.
+	fn unload(&mut self);
+	/// #### store
+	/// Returns the store of this type of resource in the registry.
+	fn store(assets: &mut Assets) -> &mut Store<Self>;
+}
+impl Resource for TextureRl {
+	fn unload(&mut self) { TextureRl::unload(self) }
+	fn store(assets: &mut Assets) -> &mut Store<Self> { &mut assets.textures }
+}
+impl Resource for Font {
+	fn unload(&mut self) { Font::unload(self) }
+	fn store(assets: &mut Assets) -> &mut Store<Self> { &mut assets.fonts }
+}
+impl Resource for Sound {
+	fn unload(&mut self) { Sound::unload(self) }
+	fn store(assets: &mut Assets) -> &mut Store<Self> { &mut assets.sounds }
+}
+impl Resource for Music {
+	fn unload(&mut self) { Music::unload(*self) }
+	fn store(assets: &mut Assets) -> &mut Store<Self> { &mut assets.music }
+}
+impl Resource for ModelRl {
+	fn unload(&mut self) { ModelRl::unload(self) }
+	fn store(assets: &mut Assets) -> &mut Store<Self> { &mut assets.models }
+}
+impl Resource for Shader {
+	fn unload(&mut self) { Shader::unload(self) }
+	fn store(assets: &mut Assets) -> &mut Store<Self> { &mut assets.shaders }
+}
+
+/// #### Watch
+/// Files a resource was loaded from, with their modification times and how to load it again.
+struct Watch<T> {
+	files: Vec<(String, Option<SystemTime>)>,
+	reload: Box<dyn Fn() -> Option<T>>,
+}
+impl<T> Debug for Watch<T> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Watch").field("files", &self.files).finish()
+	}
+}
+
+/// #### Slot
+/// Shared storage of a single resource, unloaded when the last Handle is dropped.
+#[derive(Debug)]
+struct Slot<T: Resource> {
+	key: String,
+	resource: RefCell<Option<T>>,
+	watch: RefCell<Option<Watch<T>>>,
+}
+impl<T: Resource> Slot<T> {
+	
+	/// #### unload
+	/// Unloads the resource if it hasn't been already.
+	fn unload(&self) {
+		if let Some(mut resource) = self.resource.borrow_mut().take() { resource.unload() }
+	}
+	/// #### reload_if_changed
+	/// Loads the resource again in place if any of its files were modified.
+	/// 
+	/// Returns None if nothing changed, otherwise whether the reload succeeded. The old resource is kept when it fails.
+	/// 
+	/// While the resource is borrowed through a Handle the reload waits for the next poll.
+	fn reload_if_changed(&self) -> Option<bool> {
+		let mut resource = self.resource.try_borrow_mut().ok()?;
+		let mut watch = self.watch.borrow_mut();
+		let watch = watch.as_mut()?;
+		
+		let mut changed = false;
+		for (filename, time) in watch.files.iter_mut() {
+			let modified = modified(filename);
+			if modified != *time {
+				*time = modified;
+				changed = true;
+			}
+		}
+		if !changed { return None }
+		
+		let Some(reloaded) = (watch.reload)() else { return Some(false) };
+		if let Some(mut old) = resource.replace(reloaded) { old.unload() }
+		
+		Some(true)
+	}
+	
+}
+impl<T: Resource> Drop for Slot<T> {
+	fn drop(&mut self) {
+		self.unload();
+	}
+}
+
+/// #### Handle
+/// Reference counted handle to a resource in Assets.
+/// 
+/// Handles to the same key share the resource, which is unloaded once the last one is dropped or when Pleroma closes.
+#[derive(Debug)]
+pub struct Handle<T: Resource> {
+	slot: Rc<Slot<T>>,
+}
+impl<T: Resource> Clone for Handle<T> {
+	fn clone(&self) -> Self {
+		Self { slot: self.slot.clone() }
+	}
+}
+impl<T: Resource> PartialEq for Handle<T> {
+	fn eq(&self, other: &Self) -> bool {
+		Rc::ptr_eq(&self.slot, &other.slot)
+	}
+}
+impl<T: Resource> Handle<T> {
+	
+	/// #### get
+	/// Borrows the resource, or returns None if it has been unloaded.
+	pub fn get(&self) -> Option<Ref<'_, T>> {
+		Ref::filter_map(self.slot.resource.borrow(), |resource| resource.as_ref()).ok()
+	}
+	/// #### get_mut
+	/// Mutably borrows the resource, or returns None if it has been unloaded.
+	pub fn get_mut(&self) -> Option<RefMut<'_, T>> {
+		RefMut::filter_map(self.slot.resource.borrow_mut(), |resource| resource.as_mut()).ok()
+	}
+	/// #### key
+	/// Returns the path, or name, the resource was registered under.
+	pub fn key(&self) -> &str {
+		&self.slot.key
+	}
+	/// #### references
+	/// Returns the number of handles sharing the resource.
+	pub fn references(&self) -> usize {
+		Rc::strong_count(&self.slot)
+	}
+	/// #### is_loaded
+	/// Checks whether the resource is still loaded.
+	pub fn is_loaded(&self) -> bool {
+		self.slot.resource.borrow().is_some()
+	}
+	
+}
+impl<T: Resource + Copy> Handle<T> {
+	
+	/// #### copy
+	/// Returns a copy of the raw resource, for passing to drawing functions.
+	/// 
+	/// The copy must not be unloaded or kept past the handle.
+	pub fn copy(&self) -> Option<T> {
+		*self.slot.resource.borrow()
+	}
+	
+}
+
+/// #### Store
+/// Resources of a single type in Assets, by key.
+#[derive(Debug, Clone)]
+pub struct Store<T: Resource> {
+	slots: HashMap<String, Weak<Slot<T>>>,
+}
+impl<T: Resource> Default for Store<T> {
+	fn default() -> Self {
+		Self { slots: HashMap::new() }
+	}
+}
+impl<T: Resource> Store<T> {
+	
+	/// #### get
+	/// Returns a new handle to the resource if it is still alive.
+	fn get(&self, key: &str) -> Option<Handle<T>> {
+		Some(Handle { slot: self.slots.get(key)?.upgrade()? })
+	}
+	/// #### live
+	/// Returns every slot that still has handles.
+	fn live(&self) -> Vec<Rc<Slot<T>>> {
+		self.slots.values().filter_map(|slot| slot.upgrade()).collect()
+	}
+	/// #### collect
+	/// Forgets the slots whose handles have all been dropped.
+	fn collect(&mut self) {
+		self.slots.retain(|_, slot| slot.strong_count() > 0);
+	}
+	/// #### reload_changed
+	/// Reloads every resource whose files were modified, returning their keys and whether it succeeded.
+	fn reload_changed(&self) -> Vec<(String, bool)> {
+		self.live().iter()
+			.filter_map(|slot| Some((slot.key.clone(), slot.reload_if_changed()?)))
+			.collect()
+	}
+	
+}
+
+/// #### Assets
+/// Registry of loaded resources.
+/// 
+/// Loading the same path twice returns a handle to the same resource instead of loading it again.
+/// 
+/// With hot reload enabled, textures, fonts, sounds and shaders loaded from files are reloaded in place when the files change.
+#[derive(Debug, Clone, Default)]
+pub struct Assets {
+	textures: Store<TextureRl>,
+	fonts: Store<Font>,
+	sounds: Store<Sound>,
+	music: Store<Music>,
+	models: Store<ModelRl>,
+	shaders: Store<Shader>,
+	
+	//* Hot reload */
+	hot_reload: bool,
+	poll_interval: Duration,
+	last_poll: Option<Instant>,
+}
+impl Assets {
+	
+	/// #### new
+	/// Creates an empty registry.
+	pub fn new() -> Self {
+		Self::default()
+	}
+	
+	//= Loading
+	/// #### texture
+	/// Loads a texture from file, or returns the already loaded one.
+	pub fn texture(&mut self, filename: &str) -> Option<Handle<TextureRl>> {
+		let path = filename.to_string();
+		
+		self.fetch_watched(filename, &[filename], move || {
+			let texture = TextureRl::load(&path);
+			if texture.is_ready() { Some(texture) } else { None }
+		})
+	}
+	/// #### font
+	/// Loads a font of the input size from file, or returns the already loaded one.
+	pub fn font(&mut self, filename: &str, font_size: i32) -> Option<Handle<Font>> {
+		let path = filename.to_string();
+		
+		self.fetch_watched(&format!("{filename}@{font_size}"), &[filename], move || {
+			let font = Font::load_ex(&path, font_size, Vec::new());
+			if font.ready() { Some(font) } else { None }
+		})
+	}
+	/// #### sound
+	/// Loads a sound from file, or returns the already loaded one.
+	pub fn sound(&mut self, filename: &str) -> Option<Handle<Sound>> {
+		let path = filename.to_string();
+		
+		self.fetch_watched(filename, &[filename], move || {
+			let sound = Sound::load(&path);
+			if sound.ready() { Some(sound) } else { None }
+		})
+	}
+	/// #### music
+	/// Loads a music stream from file, or returns the already loaded one.
+	pub fn music(&mut self, filename: &str) -> Option<Handle<Music>> {
+		self.fetch(filename, || {
+			let music = Music::load(filename);
+			if music.ready() { Some(music) } else { None }
+		})
+	}
+	/// #### model
+	/// Loads a model from file, or returns the already loaded one.
+	pub fn model(&mut self, filename: &str) -> Option<Handle<ModelRl>> {
+		self.fetch(filename, || {
+			let mut model = ModelRl::load(filename);
+			if model.ready() { Some(model) } else { None }
+		})
+	}
+	/// #### shader
+	/// Loads a shader from its vertex and fragment files, or returns the already loaded one.
+	/// 
+	/// Passing None uses Raylib's default shader for that stage.
+	pub fn shader(&mut self, vs_filename: Option<&str>, fs_filename: Option<&str>) -> Option<Handle<Shader>> {
+		let key = format!("{}|{}", vs_filename.unwrap_or_default(), fs_filename.unwrap_or_default());
+		let files: Vec<&str> = vs_filename.into_iter().chain(fs_filename).collect();
+		let vs_path = vs_filename.map(|filename| filename.to_string());
+		let fs_path = fs_filename.map(|filename| filename.to_string());
+		
+		self.fetch_watched(&key, &files, move || {
+			let vs_code = match &vs_path { Some(filename) => Some(fs::read_to_string(filename).ok()?), None => None };
+			let fs_code = match &fs_path { Some(filename) => Some(fs::read_to_string(filename).ok()?), None => None };
+			
+			let mut shader = Shader::load_from_memory(vs_code.as_deref(), fs_code.as_deref())?;
+			if shader.ready() { Some(shader) } else { None }
+		})
+	}
+	
+	//= Management
+	/// #### insert
+	/// Registers an already loaded resource under the key, such as one finished by the Loader.
+	/// 
+	/// If the key is in use its resource is unloaded and replaced, so existing handles see the new one.
+	pub fn insert<T: Resource>(&mut self, key: &str, resource: T) -> Handle<T> {
+		if let Some(handle) = T::store(self).get(key) {
+			handle.slot.unload();
+			*handle.slot.resource.borrow_mut() = Some(resource);
+			
+			return handle
+		}
+		
+		let slot = Rc::new(Slot { key: key.to_string(), resource: RefCell::new(Some(resource)), watch: RefCell::new(None) });
+		let store = T::store(self);
+		store.collect();
+		store.slots.insert(key.to_string(), Rc::downgrade(&slot));
+		
+		Handle { slot }
+	}
+	/// #### get
+	/// Returns a handle to the resource registered under the key if it is still loaded.
+	pub fn get<T: Resource>(&mut self, key: &str) -> Option<Handle<T>> {
+		T::store(self).get(key)
+	}
+	/// #### count
+	/// Returns the number of resources currently loaded.
+	pub fn count(&self) -> usize {
+		self.textures.live().len() +
+		self.fonts.live().len() +
+		self.sounds.live().len() +
+		self.music.live().len() +
+		self.models.live().len() +
+		self.shaders.live().len()
+	}
+	/// #### unload_all
+	/// Unloads every resource, leaving existing handles empty.
+	pub fn unload_all(&mut self) -> &mut Self {
+		for slot in self.textures.live() { slot.unload() }
+		for slot in self.fonts.live() { slot.unload() }
+		for slot in self.sounds.live() { slot.unload() }
+		for slot in self.music.live() { slot.unload() }
+		for slot in self.models.live() { slot.unload() }
+		for slot in self.shaders.live() { slot.unload() }
+		
+		*self = Self {
+			hot_reload: self.hot_reload,
+			poll_interval: self.poll_interval,
+			..Default::default()
+		};
+		
+		self
+	}
+	
+	//= Hot reload
+	/// #### set_hot_reload
+	/// Sets whether resources are reloaded when their files change, checking at most once per interval.
+	pub fn set_hot_reload(&mut self, enabled: bool, poll_interval: Duration) -> &mut Self {
+		self.hot_reload = enabled;
+		self.poll_interval = poll_interval;
+		
+		self
+	}
+	/// #### watch
+	/// Reloads the resource with the input function whenever one of the files changes.
+	/// 
+	/// Resources loaded from files by Assets are already watched, this is for ones added with insert.
+	pub fn watch<T: Resource>(&mut self, handle: &Handle<T>, files: &[&str], reload: impl Fn() -> Option<T> + 'static) -> &mut Self {
+		let files = files.iter().map(|filename| (filename.to_string(), modified(filename))).collect();
+		*handle.slot.watch.borrow_mut() = Some(Watch { files, reload: Box::new(reload) });
+		
+		self
+	}
+	/// #### update
+	/// Reloads changed resources if hot reload is enabled and the poll interval has passed.
+	/// 
+	/// Returns the keys of the resources that changed and whether reloading them succeeded.
+	pub fn update(&mut self) -> Vec<(String, bool)> {
+		if !self.hot_reload { return Vec::new() }
+		if self.last_poll.is_some_and(|last| last.elapsed() < self.poll_interval) { return Vec::new() }
+		self.last_poll = Some(Instant::now());
+		
+		self.reload_changed()
+	}
+	/// #### reload_changed
+	/// Reloads every resource whose files were modified since it was loaded, whether or not hot reload is enabled.
+	pub fn reload_changed(&mut self) -> Vec<(String, bool)> {
+		let mut reloaded = self.textures.reload_changed();
+		reloaded.extend(self.fonts.reload_changed());
+		reloaded.extend(self.sounds.reload_changed());
+		reloaded.extend(self.shaders.reload_changed());
+		
+		reloaded
+	}
+	
+	/// #### fetch
+	/// Returns the resource under the key, loading it if it isn't already.
+	fn fetch<T: Resource>(&mut self, key: &str, load: impl FnOnce() -> Option<T>) -> Option<Handle<T>> {
+		if let Some(handle) = T::store(self).get(key) { return Some(handle) }
+		
+		Some(self.insert(key, load()?))
+	}
+	/// #### fetch_watched
+	/// Returns the resource under the key, loading it and remembering how to reload it if it isn't already.
+	fn fetch_watched<T: Resource>(&mut self, key: &str, files: &[&str], load: impl Fn() -> Option<T> + 'static) -> Option<Handle<T>> {
+		if let Some(handle) = T::store(self).get(key) { return Some(handle) }
+		
+		let handle = self.insert(key, load()?);
+		self.watch(&handle, files, load);
+		
+		Some(handle)
+	}
+	
+}
+
+/// #### modified
+/// Returns the modification time of the file, if it can be read.
+fn modified(filename: &str) -> Option<SystemTime> {
+	fs::metadata(filename).and_then(|metadata| metadata.modified()).ok()
+}
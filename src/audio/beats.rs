@@ -0,0 +1,185 @@
+use super::{midi::*, playlist::*};
+
+
+/// Distance in beats positions are nudged by so a boundary is only passed once.
+const NUDGE: f64 = 1e-9;
+
+/// #### MusicEvent
+/// Something that happened in the playing music during a frame.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MusicEvent {
+	/// Beat of the song counted from 0, with the bar it's in and its place in the bar, both from 0.
+	Beat { index: u64, bar: u64, beat: u32 },
+	/// First beat of a bar, counted from 0. Comes right before that Beat.
+	Bar(u64),
+	/// Named marker of the song.
+	Marker(String),
+}
+
+/// #### BeatMap
+/// Where the beats, bars and markers of a song fall, in beats from its first beat.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BeatMap {
+	/// Position playback starts at, below 0.0 when the song starts before its first beat.
+	pub start: f64,
+	/// Position playback jumps back to when it loops.
+	pub loop_start: f64,
+	/// Position playback loops from.
+	pub loop_end: f64,
+	pub beats_per_bar: u32,
+	/// Markers as their name and position.
+	pub markers: Vec<(String, f64)>,
+}
+impl BeatMap {
+	
+	/// #### events
+	/// Returns the beats, bars and markers after one position up to and including another, in order.
+	pub fn events(&self, from: f64, to: f64) -> Vec<MusicEvent> {
+		let mut events: Vec<(f64, MusicEvent)> = Vec::new();
+		let beats_per_bar = self.beats_per_bar.max(1) as u64;
+		
+		let mut beat = (from.floor() + 1.0).max(0.0);
+		while beat <= to {
+			let index = beat as u64;
+			let bar = index / beats_per_bar;
+			let place = (index % beats_per_bar) as u32;
+			if place == 0 { events.push((beat, MusicEvent::Bar(bar))); }
+			events.push((beat, MusicEvent::Beat { index, bar, beat: place }));
+			beat += 1.0;
+		}
+		for (name, position) in self.markers.iter().filter(|(_, position)| *position > from && *position <= to) {
+			events.push((*position, MusicEvent::Marker(name.clone())));
+		}
+		events.sort_by(|a, b| a.0.total_cmp(&b.0));
+		
+		events.into_iter().map(|(_, event)| event).collect()
+	}
+	
+}
+
+/// #### BeatClock
+/// Follows the position of the playing music, turning how far it moved each frame into MusicEvents.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct BeatClock {
+	position: Option<f64>,
+}
+impl BeatClock {
+	
+	/// #### position
+	/// Returns the position the music was last at in beats, or None if it hasn't moved since it restarted.
+	pub fn position(&self) -> Option<f64> {
+		self.position
+	}
+	/// #### restart
+	/// Starts over for a new song, so the next advance covers everything from the start of the song.
+	pub fn restart(&mut self) -> &mut Self {
+		self.position = None;
+		
+		self
+	}
+	/// #### sync
+	/// Jumps to the position without the events on the way, for music that was already playing.
+	pub fn sync(&mut self, position: f64) -> &mut Self {
+		self.position = Some(position);
+		
+		self
+	}
+	/// #### advance
+	/// Moves to the position in beats, returning the events passed since the last one.
+	/// 
+	/// After a restart the events at the start of the song are included.
+	/// Moving backwards means the song looped, passing the rest of the loop and the start of the loop again.
+	pub fn advance(&mut self, position: f64, map: &BeatMap) -> Vec<MusicEvent> {
+		let previous = self.position.unwrap_or(map.start - NUDGE);
+		self.position = Some(position);
+		
+		if position >= previous { return map.events(previous, position) }
+		
+		//* The loop end is the same moment as the loop start, so it's left to the start */
+		let mut events = map.events(previous, map.loop_end - NUDGE);
+		events.extend(map.events(map.loop_start.min(position) - NUDGE, position));
+		events
+	}
+	
+}
+
+/// #### Tempo
+/// Tempo of a streamed song, which can't be read from the file.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tempo {
+	pub bpm: f32,
+	pub beats_per_bar: u32,
+	/// Seconds into the song its first beat is at.
+	pub offset: f32,
+}
+impl Default for Tempo {
+	fn default() -> Self {
+		Self {
+			bpm: 120.0,
+			beats_per_bar: 4,
+			offset: 0.0,
+		}
+	}
+}
+
+impl Song {
+	
+	/// #### with_tempo
+	/// Gives the Song a tempo, so it sends beats and bars while it plays.
+	pub fn with_tempo(mut self, bpm: f32, beats_per_bar: u32, offset: f32) -> Self {
+		self.tempo = Some(Tempo { bpm, beats_per_bar, offset });
+		
+		self
+	}
+	/// #### with_marker
+	/// Adds a named marker the seconds into the Song, sent when playback passes it. Markers need a tempo.
+	pub fn with_marker(mut self, name: &str, seconds: f32) -> Self {
+		self.markers.push((name.to_string(), seconds));
+		
+		self
+	}
+	/// #### beat
+	/// Returns the position in beats the seconds into the Song, or None without a tempo.
+	pub fn beat(&self, seconds: f32) -> Option<f64> {
+		let tempo = self.tempo?;
+		
+		Some((seconds - tempo.offset) as f64 * tempo.bpm as f64 / 60.0)
+	}
+	/// #### beat_map
+	/// Returns where the beats and markers of the Song fall, given its length in seconds, or None without a tempo.
+	pub fn beat_map(&self, length: f32) -> Option<BeatMap> {
+		let tempo = self.tempo?;
+		
+		Some(BeatMap {
+			start: self.beat(0.0)?,
+			loop_start: self.beat(self.loop_start.unwrap_or(0.0))?,
+			loop_end: self.beat(self.loop_end.unwrap_or(length))?,
+			beats_per_bar: tempo.beats_per_bar,
+			markers: self.markers.iter().filter_map(|(name, seconds)| Some((name.clone(), self.beat(*seconds)?))).collect(),
+		})
+	}
+	
+}
+
+impl Midi {
+	
+	/// #### beat
+	/// Returns the position of the track in beats.
+	pub fn beat(&self) -> f64 {
+		self.tick / self.ticks_per_beat.max(1) as f64
+	}
+	/// #### beat_map
+	/// Returns where the beats and markers of the track fall. Beats follow tempo changes, as they're counted in ticks.
+	pub fn beat_map(&self) -> BeatMap {
+		let ticks_per_beat = self.ticks_per_beat.max(1) as f64;
+		
+		BeatMap {
+			start: 0.0,
+			loop_start: 0.0,
+			loop_end: self.end as f64 / ticks_per_beat,
+			beats_per_bar: self.beats_per_bar,
+			markers: self.markers.iter().map(|(name, tick)| (name.clone(), *tick as f64 / ticks_per_beat)).collect(),
+		}
+	}
+	
+}
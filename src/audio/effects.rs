@@ -0,0 +1,647 @@
+use std::{cell::UnsafeCell, f32::consts::PI, fmt::Display, os::raw::c_void, ptr::null_mut, sync::atomic::{AtomicPtr, AtomicUsize, Ordering}};
+
+use crate::sound::*;
+
+
+/// Sample rate chains are made at, and run at if the audio device's rate can't be read.
+pub const EFFECT_SAMPLE_RATE: u32 = 48000;
+/// Longest delay time in seconds.
+pub const MAX_DELAY: f32 = 2.0;
+/// Number of streams effects can be attached to at once, the master output included.
+pub const MAX_PROCESSORS: usize = 64;
+
+/// #### EffectError
+/// Errors that can occur while attaching effect chains.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EffectError {
+	/// Every processor slot is taken, so the chain isn't running on some streams of its target.
+	OutOfSlots,
+}
+impl Display for EffectError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			EffectError::OutOfSlots	=> write!(f, "Every effect processor slot is taken."),
+		}
+	}
+}
+
+/// #### device_sample_rate
+/// Returns the sample rate Raylib mixes at, or None if the audio device isn't ready.
+/// 
+/// Processors get audio after it's converted to the device's rate, whatever the stream's own rate.
+/// Raylib doesn't expose the rate, but converts Sounds to it when loading them, so it's read off a one frame Sound.
+pub fn device_sample_rate() -> Option<u32> {
+	let mut wave = Wave::from_samples(&[0.0; 2], EFFECT_SAMPLE_RATE, 2);
+	let mut sound = wave.to_sound();
+	wave.unload();
+	if !sound.ready() { return None }
+	
+	let rate = sound.stream().sample_rate();
+	sound.unload();
+	
+	Some(rate).filter(|rate| *rate > 0)
+}
+
+/// #### Effect
+/// DSP effect with its parameters.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Effect {
+	/// Cuts frequencies above the cutoff in hertz, muffling the sound.
+	LowPass { cutoff: f32 },
+	/// Cuts frequencies below the cutoff in hertz, thinning the sound.
+	HighPass { cutoff: f32 },
+	/// Echo after the time in seconds, fed back into itself by the feedback and mixed in by the mix.
+	Delay { time: f32, feedback: f32, mix: f32 },
+	/// Room reverb, with room size, damping and mix from 0.0 to 1.0.
+	Reverb { room_size: f32, damping: f32, mix: f32 },
+	/// Lowers the bit depth and holds each sample for the downsample in frames.
+	Bitcrusher { bits: f32, downsample: f32 },
+	/// Keeps peaks under the threshold, recovering over the release in seconds.
+	Limiter { threshold: f32, release: f32 },
+}
+impl Effect {
+	
+	/// #### lerp
+	/// Returns the effect part way to the target, or None if the target is a different kind of effect.
+	pub fn lerp(&self, target: &Effect, amount: f32) -> Option<Effect> {
+		let mix = |a: f32, b: f32| a + (b - a) * amount;
+		
+		Some(match (*self, *target) {
+			(Effect::LowPass { cutoff: a }, Effect::LowPass { cutoff: b }) => Effect::LowPass { cutoff: mix(a, b) },
+			(Effect::HighPass { cutoff: a }, Effect::HighPass { cutoff: b }) => Effect::HighPass { cutoff: mix(a, b) },
+			(Effect::Delay { time: a, feedback: b, mix: c }, Effect::Delay { time: x, feedback: y, mix: z }) => {
+				Effect::Delay { time: mix(a, x), feedback: mix(b, y), mix: mix(c, z) }
+			}
+			(Effect::Reverb { room_size: a, damping: b, mix: c }, Effect::Reverb { room_size: x, damping: y, mix: z }) => {
+				Effect::Reverb { room_size: mix(a, x), damping: mix(b, y), mix: mix(c, z) }
+			}
+			(Effect::Bitcrusher { bits: a, downsample: b }, Effect::Bitcrusher { bits: x, downsample: y }) => {
+				Effect::Bitcrusher { bits: mix(a, x), downsample: mix(b, y) }
+			}
+			(Effect::Limiter { threshold: a, release: b }, Effect::Limiter { threshold: x, release: y }) => {
+				Effect::Limiter { threshold: mix(a, x), release: mix(b, y) }
+			}
+			_ => return None,
+		})
+	}
+	/// #### same_kind
+	/// Checks whether the effects are the same kind, whatever their parameters.
+	pub fn same_kind(&self, other: &Effect) -> bool {
+		std::mem::discriminant(self) == std::mem::discriminant(other)
+	}
+	
+}
+
+/// #### State
+/// Memory an effect keeps between buffers.
+#[derive(Debug, Clone)]
+enum State {
+	Filter([f32; 2]),
+	Delay { buffer: Vec<[f32; 2]>, position: usize },
+	Reverb(Box<Reverb>),
+	Bitcrusher { held: [f32; 2], counter: f32 },
+	Limiter { envelope: f32 },
+}
+impl State {
+	
+	/// #### new
+	/// Creates the empty state of the effect.
+	fn new(effect: &Effect, sample_rate: u32) -> Self {
+		match effect {
+			Effect::LowPass { .. } | Effect::HighPass { .. } => State::Filter([0.0; 2]),
+			Effect::Delay { .. } => State::Delay {
+				buffer: vec![[0.0; 2]; (MAX_DELAY * sample_rate as f32) as usize + 1],
+				position: 0,
+			},
+			Effect::Reverb { .. }		=> State::Reverb(Box::new(Reverb::new(sample_rate))),
+			Effect::Bitcrusher { .. }	=> State::Bitcrusher { held: [0.0; 2], counter: 0.0 },
+			Effect::Limiter { .. }		=> State::Limiter { envelope: 0.0 },
+		}
+	}
+	
+}
+
+/// #### Reverb
+/// Schroeder reverb of parallel comb filters into allpass filters, for each channel.
+#[derive(Debug, Clone)]
+struct Reverb {
+	combs: [Vec<(Vec<f32>, usize, f32)>; 2],
+	allpasses: [Vec<(Vec<f32>, usize)>; 2],
+}
+impl Reverb {
+	
+	/// Comb and allpass lengths in samples at 44100hz, the right channel slightly longer to spread it.
+	const COMBS: [usize; 4] = [1116, 1188, 1277, 1356];
+	const ALLPASSES: [usize; 2] = [556, 441];
+	const SPREAD: usize = 23;
+	
+	/// #### new
+	fn new(sample_rate: u32) -> Self {
+		let scale = |length: usize| (length as f32 * sample_rate as f32 / 44100.0).max(1.0) as usize;
+		let combs = |spread: usize| Self::COMBS.iter().map(|length| (vec![0.0; scale(length + spread)], 0, 0.0)).collect();
+		let allpasses = |spread: usize| Self::ALLPASSES.iter().map(|length| (vec![0.0; scale(length + spread)], 0)).collect();
+		
+		Self {
+			combs: [combs(0), combs(Self::SPREAD)],
+			allpasses: [allpasses(0), allpasses(Self::SPREAD)],
+		}
+	}
+	/// #### process
+	/// Returns the reverberated sample of the channel.
+	fn process(&mut self, channel: usize, input: f32, room_size: f32, damping: f32) -> f32 {
+		let feedback = 0.7 + 0.28 * room_size.clamp(0.0, 1.0);
+		let damping = damping.clamp(0.0, 1.0) * 0.4;
+		
+		let mut output = 0.0;
+		for (buffer, position, filter) in self.combs[channel].iter_mut() {
+			let delayed = buffer[*position];
+			*filter = delayed * (1.0 - damping) + *filter * damping;
+			buffer[*position] = input * 0.015 + *filter * feedback;
+			*position = (*position + 1) % buffer.len();
+			output += delayed;
+		}
+		for (buffer, position) in self.allpasses[channel].iter_mut() {
+			let delayed = buffer[*position];
+			buffer[*position] = output + delayed * 0.5;
+			*position = (*position + 1) % buffer.len();
+			output = delayed - output;
+		}
+		
+		output
+	}
+	
+}
+
+/// #### EffectChain
+/// Effects applied one after the other to interleaved stereo samples.
+#[derive(Debug, Clone)]
+pub struct EffectChain {
+	pub sample_rate: u32,
+	
+	effects: Vec<Effect>,
+	states: Vec<State>,
+}
+impl EffectChain {
+	
+	/// #### new
+	/// Creates a chain of the effects at EFFECT_SAMPLE_RATE. Copies running on streams use the device's rate.
+	pub fn new(effects: Vec<Effect>) -> Self {
+		Self::with_sample_rate(effects, EFFECT_SAMPLE_RATE)
+	}
+	/// #### with_sample_rate
+	/// Creates a chain of the effects processing audio at the sample rate.
+	pub fn with_sample_rate(effects: Vec<Effect>, sample_rate: u32) -> Self {
+		Self {
+			states: effects.iter().map(|effect| State::new(effect, sample_rate)).collect(),
+			effects,
+			sample_rate,
+		}
+	}
+	/// #### effects
+	/// Returns the effects of the chain in order.
+	pub fn effects(&self) -> &[Effect] {
+		&self.effects
+	}
+	/// #### set_effect
+	/// Replaces the effect at the index, keeping its memory if it's the same kind so parameters can change smoothly.
+	pub fn set_effect(&mut self, index: usize, effect: Effect) -> &mut Self {
+		let Some(current) = self.effects.get_mut(index) else { return self };
+		
+		if !current.same_kind(&effect) { self.states[index] = State::new(&effect, self.sample_rate); }
+		*current = effect;
+		
+		self
+	}
+	/// #### clear
+	/// Forgets the memory of every effect, cutting off echoes and reverb tails.
+	pub fn clear(&mut self) -> &mut Self {
+		self.states = self.effects.iter().map(|effect| State::new(effect, self.sample_rate)).collect();
+		
+		self
+	}
+	/// #### process
+	/// Applies the effects in place to interleaved stereo samples.
+	pub fn process(&mut self, samples: &mut [f32]) {
+		let rate = self.sample_rate.max(1) as f32;
+		
+		for (effect, state) in self.effects.iter().zip(self.states.iter_mut()) {
+			for frame in samples.chunks_exact_mut(2) {
+				match (effect, &mut *state) {
+					(Effect::LowPass { cutoff }, State::Filter(memory)) | (Effect::HighPass { cutoff }, State::Filter(memory)) => {
+						let amount = 1.0 - (-2.0 * PI * cutoff.max(0.0) / rate).exp();
+						for channel in 0..2 {
+							memory[channel] += amount * (frame[channel] - memory[channel]);
+							frame[channel] = match effect {
+								Effect::LowPass { .. } => memory[channel],
+								_ => frame[channel] - memory[channel],
+							};
+						}
+					}
+					(Effect::Delay { time, feedback, mix }, State::Delay { buffer, position }) => {
+						let delay = ((time.clamp(0.0, MAX_DELAY) * rate) as usize).clamp(1, buffer.len() - 1);
+						let read = (*position + buffer.len() - delay) % buffer.len();
+						for channel in 0..2 {
+							let delayed = buffer[read][channel];
+							buffer[*position][channel] = frame[channel] + delayed * feedback;
+							frame[channel] += delayed * mix;
+						}
+						*position = (*position + 1) % buffer.len();
+					}
+					(Effect::Reverb { room_size, damping, mix }, State::Reverb(reverb)) => {
+						for (channel, sample) in frame.iter_mut().enumerate() {
+							let wet = reverb.process(channel, *sample, *room_size, *damping);
+							*sample = *sample * (1.0 - mix) + wet * mix;
+						}
+					}
+					(Effect::Bitcrusher { bits, downsample }, State::Bitcrusher { held, counter }) => {
+						if *counter <= 0.0 {
+							let step = 2.0 / 2f32.powf(bits.clamp(1.0, 32.0));
+							for channel in 0..2 { held[channel] = (frame[channel] / step).round() * step; }
+							*counter += downsample.max(1.0);
+						}
+						*counter -= 1.0;
+						frame.copy_from_slice(held);
+					}
+					(Effect::Limiter { threshold, release }, State::Limiter { envelope }) => {
+						let peak = frame[0].abs().max(frame[1].abs());
+						if peak > *envelope {
+							*envelope = peak;
+						} else {
+							*envelope += (peak - *envelope) * (1.0 - (-1.0 / (release.max(0.001) * rate)).exp());
+						}
+						if *envelope > *threshold {
+							let gain = threshold / *envelope;
+							frame[0] *= gain;
+							frame[1] *= gain;
+						}
+					}
+					_ => {}
+				}
+			}
+		}
+	}
+	
+}
+
+/// #### Target
+/// What an effect chain is attached to.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Target {
+	/// The song, and any song fading out.
+	Music,
+	/// Every sfx voice and emitter playing through the bus.
+	/// 
+	/// Raylib has no submixes, so each voice and emitter runs its own copy taking up one of the MAX_PROCESSORS slots.
+	/// An sfx takes SFX_VOICES slots, so a bus with more than a dozen sfx runs out.
+	Bus(String),
+	/// Everything mixed together, right before the output.
+	Master,
+}
+
+/// #### EffectChainId
+/// Handle to an effect chain added to the AudioHandler.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EffectChainId(pub(crate) usize);
+
+//= Raylib processors
+
+/// Processors running on streams by slot, null for a free slot. Only the main thread changes them.
+static PROCESSORS: [AtomicPtr<Processor>; MAX_PROCESSORS] = [const { AtomicPtr::new(null_mut()) }; MAX_PROCESSORS];
+
+/// Bit of Processor::middle set when it holds effects the audio thread hasn't picked up.
+const FRESH: usize = 4;
+
+/// #### Processor
+/// Copy of a chain running on a stream, with its own memory.
+/// 
+/// The chain belongs to the audio thread while attached. New parameters are handed over through a triple buffer,
+/// so neither thread ever waits on the other or allocates for the other.
+struct Processor {
+	chain: UnsafeCell<EffectChain>,
+	effects: [UnsafeCell<Vec<Effect>>; 3],
+	/// Buffer between the threads, with FRESH if the main thread wrote it last.
+	middle: AtomicUsize,
+	/// Buffer the main thread writes, only touched by it.
+	writer: UnsafeCell<usize>,
+	/// Buffer the audio thread reads, only touched by it.
+	reader: UnsafeCell<usize>,
+}
+unsafe impl Sync for Processor {}
+impl Processor {
+	
+	/// #### new
+	fn new(chain: EffectChain) -> Self {
+		let effects = chain.effects().to_vec();
+		
+		Self {
+			chain: UnsafeCell::new(chain),
+			effects: [UnsafeCell::new(effects.clone()), UnsafeCell::new(effects.clone()), UnsafeCell::new(effects)],
+			middle: AtomicUsize::new(1),
+			writer: UnsafeCell::new(0),
+			reader: UnsafeCell::new(2),
+		}
+	}
+	/// #### publish
+	/// Hands the effects, the same kinds as the running ones, to the audio thread. Only called on the main thread.
+	fn publish(&self, effects: &[Effect]) {
+		unsafe {
+			let writer = &mut *self.writer.get();
+			(*self.effects[*writer].get()).copy_from_slice(effects);
+			*writer = self.middle.swap(*writer | FRESH, Ordering::AcqRel) & !FRESH;
+		}
+	}
+	/// #### run
+	/// Picks up the latest effects and applies them to the samples. Only called on the audio thread.
+	fn run(&self, samples: &mut [f32]) {
+		unsafe {
+			let chain = &mut *self.chain.get();
+			if self.middle.load(Ordering::Acquire) & FRESH != 0 {
+				let reader = &mut *self.reader.get();
+				*reader = self.middle.swap(*reader, Ordering::AcqRel) & !FRESH;
+				for (index, effect) in (*self.effects[*reader].get()).iter().enumerate() { chain.set_effect(index, *effect); }
+			}
+			chain.process(samples);
+		}
+	}
+	/// #### reset
+	/// Replaces the effect in the chain and in every buffer, for effects of a new kind. Only called while detached.
+	fn reset(&mut self, index: usize, effect: Effect) {
+		self.chain.get_mut().set_effect(index, effect);
+		let effects = self.chain.get_mut().effects().to_vec();
+		for buffer in self.effects.iter_mut() { buffer.get_mut().clone_from(&effects); }
+		*self.middle.get_mut() &= !FRESH;
+	}
+	
+}
+
+/// #### run_processor
+/// Applies the processor in the slot to the buffer Raylib passes it, which is always stereo 32-bit floats.
+/// 
+/// Raylib runs processors while holding its audio lock and attaches and detaches them under it too,
+/// so a processor isn't freed while it runs.
+fn run_processor(slot: usize, data: *mut c_void, frames: u32) {
+	let processor = PROCESSORS[slot].load(Ordering::Acquire);
+	if data.is_null() || processor.is_null() { return }
+	
+	let samples = unsafe{ std::slice::from_raw_parts_mut(data as *mut f32, frames as usize * 2) };
+	unsafe{ (*processor).run(samples) }
+}
+
+/// Raylib callbacks carry no user data, so every slot gets its own callback.
+macro_rules! callbacks {
+	($($slot:literal)*) => {
+		const CALLBACKS: [AudioCallback; MAX_PROCESSORS] = [$({
+			extern "C" fn callback(data: *mut c_void, frames: u32) { run_processor($slot, data, frames) }
+			callback
+		}),*];
+	};
+}
+callbacks!(
+	0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25 26 27 28 29 30 31
+	32 33 34 35 36 37 38 39 40 41 42 43 44 45 46 47 48 49 50 51 52 53 54 55 56 57 58 59 60 61 62 63
+);
+
+/// #### Attachment
+/// Processor slot running a copy of a chain on a stream, or on the master output if there's no stream.
+#[derive(Debug, Clone, Copy)]
+struct Attachment {
+	slot: usize,
+	stream: Option<AudioStream>,
+}
+impl Attachment {
+	
+	/// #### connect
+	/// Starts Raylib running the slot's processor on the stream.
+	fn connect(&self) {
+		match self.stream {
+			Some(mut stream) => { stream.attach_processor(CALLBACKS[self.slot]); }
+			None => unsafe{ AttachAudioMixedProcessor(CALLBACKS[self.slot]) },
+		}
+	}
+	/// #### disconnect
+	/// Stops Raylib running the slot's processor, after which the audio thread doesn't touch it.
+	fn disconnect(&self) {
+		match self.stream {
+			Some(mut stream) => { stream.detach_processor(CALLBACKS[self.slot]); }
+			None => unsafe{ DetachAudioMixedProcessor(CALLBACKS[self.slot]) },
+		}
+	}
+	/// #### processor
+	/// Returns the processor in the slot.
+	fn processor(&self) -> Option<&Processor> {
+		unsafe{ PROCESSORS[self.slot].load(Ordering::Acquire).as_ref() }
+	}
+	/// #### release
+	/// Takes the processor off its stream and frees it and the slot.
+	fn release(&self) {
+		self.disconnect();
+		let processor = PROCESSORS[self.slot].swap(null_mut(), Ordering::AcqRel);
+		if !processor.is_null() { drop(unsafe{ Box::from_raw(processor) }); }
+	}
+	
+}
+
+/// #### Animation
+/// Effect moving its parameters towards a target over time.
+#[derive(Debug, Clone, Copy)]
+struct Animation {
+	chain: usize,
+	index: usize,
+	from: Effect,
+	to: Effect,
+	elapsed: f32,
+	duration: f32,
+}
+
+/// #### ChainEntry
+/// Effect chain with what it's attached to.
+#[derive(Debug, Clone)]
+struct ChainEntry {
+	chain: EffectChain,
+	target: Option<Target>,
+	attachments: Vec<Attachment>,
+	/// Streams of the target left without the chain when it was last synced, for lack of a slot.
+	missing: usize,
+}
+
+/// #### Effects
+/// Effect chains, the streams they're attached to and their animations.
+/// 
+/// Every stream a chain is attached to runs its own copy of it, taking up one of the MAX_PROCESSORS slots.
+#[derive(Debug, Clone, Default)]
+pub struct Effects {
+	chains: Vec<Option<ChainEntry>>,
+	animations: Vec<Animation>,
+	/// Rate Raylib mixes at, read from the device the first time a chain is attached.
+	sample_rate: Option<u32>,
+}
+impl Effects {
+	
+	/// #### add
+	/// Adds a chain of the effects, not attached to anything yet.
+	pub fn add(&mut self, effects: Vec<Effect>) -> EffectChainId {
+		self.chains.push(Some(ChainEntry { chain: EffectChain::new(effects), target: None, attachments: Vec::new(), missing: 0 }));
+		
+		EffectChainId(self.chains.len() - 1)
+	}
+	/// #### remove
+	/// Detaches the chain from everything and forgets it.
+	pub fn remove(&mut self, id: EffectChainId) {
+		self.detach(id);
+		if let Some(entry) = self.chains.get_mut(id.0) { *entry = None; }
+		self.animations.retain(|animation| animation.chain != id.0);
+	}
+	/// #### chain
+	/// Returns the chain.
+	pub fn chain(&self, id: EffectChainId) -> Option<&EffectChain> {
+		self.chains.get(id.0)?.as_ref().map(|entry| &entry.chain)
+	}
+	/// #### target
+	/// Returns what the chain is attached to.
+	pub fn target(&self, id: EffectChainId) -> Option<&Target> {
+		self.chains.get(id.0)?.as_ref()?.target.as_ref()
+	}
+	/// #### set_target
+	/// Sets what the chain should be attached to, without attaching it. A new target takes it off the old one.
+	pub fn set_target(&mut self, id: EffectChainId, target: Option<Target>) {
+		if self.target(id) != target.as_ref() { self.detach(id); }
+		if let Some(Some(entry)) = self.chains.get_mut(id.0) { entry.target = target; }
+	}
+	/// #### targets
+	/// Returns every chain that has a target, with its target.
+	pub fn targets(&self) -> Vec<(EffectChainId, Target)> {
+		self.chains.iter().enumerate()
+			.filter_map(|(i, entry)| Some((EffectChainId(i), entry.as_ref()?.target.clone()?)))
+			.collect()
+	}
+	/// #### attach
+	/// Runs a fresh copy of the chain on the stream, or on the master output if None. Returns false if every slot is taken.
+	pub fn attach(&mut self, id: EffectChainId, stream: Option<AudioStream>) -> bool {
+		if self.sample_rate.is_none() { self.sample_rate = device_sample_rate(); }
+		let rate = self.sample_rate.unwrap_or(EFFECT_SAMPLE_RATE);
+		
+		let Some(Some(entry)) = self.chains.get_mut(id.0) else { return false };
+		let Some(slot) = PROCESSORS.iter().position(|processor| processor.load(Ordering::Acquire).is_null()) else { return false };
+		
+		let mut copy = entry.chain.clone();
+		copy.sample_rate = rate;
+		copy.clear();
+		PROCESSORS[slot].store(Box::into_raw(Box::new(Processor::new(copy))), Ordering::Release);
+		
+		let attachment = Attachment { slot, stream };
+		attachment.connect();
+		entry.attachments.push(attachment);
+		
+		true
+	}
+	/// #### sync
+	/// Attaches the chain to the streams it isn't running on yet and takes it off the ones it shouldn't run on.
+	/// 
+	/// Streams it stays on keep their copy, so echoes and reverb tails carry on. Returns false if every slot is taken.
+	pub fn sync(&mut self, id: EffectChainId, streams: &[Option<AudioStream>]) -> bool {
+		let Some(Some(entry)) = self.chains.get_mut(id.0) else { return false };
+		
+		let mut missing = streams.to_vec();
+		let mut kept = Vec::new();
+		for attachment in entry.attachments.drain(..) {
+			match missing.iter().position(|stream| *stream == attachment.stream) {
+				Some(index) => {
+					missing.swap_remove(index);
+					kept.push(attachment);
+				}
+				None => attachment.release(),
+			}
+		}
+		entry.attachments = kept;
+		
+		let failed = missing.into_iter().filter(|stream| !self.attach(id, *stream)).count();
+		if let Some(Some(entry)) = self.chains.get_mut(id.0) { entry.missing = failed; }
+		
+		failed == 0
+	}
+	/// #### detach
+	/// Takes the chain off everything it's attached to, which has to happen before those streams are unloaded.
+	pub fn detach(&mut self, id: EffectChainId) {
+		let Some(Some(entry)) = self.chains.get_mut(id.0) else { return };
+		
+		for attachment in entry.attachments.drain(..) { attachment.release(); }
+	}
+	/// #### detach_stream
+	/// Takes every chain off the stream, which has to happen before it's unloaded.
+	pub fn detach_stream(&mut self, stream: AudioStream) {
+		for entry in self.chains.iter_mut().flatten() {
+			let (released, kept) = entry.attachments.iter().partition(|attachment| attachment.stream == Some(stream));
+			entry.attachments = kept;
+			for attachment in released.iter() { attachment.release(); }
+		}
+	}
+	/// #### detach_all
+	/// Takes every chain off everything it's attached to.
+	pub fn detach_all(&mut self) {
+		for i in 0..self.chains.len() { self.detach(EffectChainId(i)); }
+	}
+	/// #### attached
+	/// Returns how many streams the chain is running on.
+	pub fn attached(&self, id: EffectChainId) -> usize {
+		self.chains.get(id.0).and_then(|entry| entry.as_ref()).map_or(0, |entry| entry.attachments.len())
+	}
+	/// #### missing
+	/// Returns how many streams of the chain's target it isn't running on, because every slot was taken.
+	pub fn missing(&self, id: EffectChainId) -> usize {
+		self.chains.get(id.0).and_then(|entry| entry.as_ref()).map_or(0, |entry| entry.missing)
+	}
+	/// #### set_effect
+	/// Replaces the effect at the index of the chain, wherever it's running.
+	/// 
+	/// Effects of the same kind are handed to the audio thread as they are. A new kind needs new memory,
+	/// so those processors are stopped for the change.
+	pub fn set_effect(&mut self, id: EffectChainId, index: usize, effect: Effect) {
+		let Some(Some(entry)) = self.chains.get_mut(id.0) else { return };
+		let Some(same_kind) = entry.chain.effects().get(index).map(|current| current.same_kind(&effect)) else { return };
+		entry.chain.set_effect(index, effect);
+		
+		for attachment in entry.attachments.iter() {
+			if same_kind {
+				if let Some(processor) = attachment.processor() { processor.publish(entry.chain.effects()); }
+			} else {
+				attachment.disconnect();
+				let processor = PROCESSORS[attachment.slot].load(Ordering::Acquire);
+				if let Some(processor) = unsafe{ processor.as_mut() } { processor.reset(index, effect); }
+				attachment.connect();
+			}
+		}
+	}
+	/// #### animate
+	/// Moves the effect at the index towards the target over the seconds. The target has to be the same kind of effect.
+	pub fn animate(&mut self, id: EffectChainId, index: usize, target: Effect, seconds: f32) {
+		let Some(from) = self.chain(id).and_then(|chain| chain.effects().get(index)).copied() else { return };
+		if !from.same_kind(&target) { return }
+		
+		self.animations.retain(|animation| animation.chain != id.0 || animation.index != index);
+		self.animations.push(Animation { chain: id.0, index, from, to: target, elapsed: 0.0, duration: seconds });
+	}
+	/// #### animating
+	/// Checks whether any effect of the chain is being animated.
+	pub fn animating(&self, id: EffectChainId) -> bool {
+		self.animations.iter().any(|animation| animation.chain == id.0)
+	}
+	/// #### update
+	/// Advances the animations by the seconds.
+	pub fn update(&mut self, seconds: f32) {
+		let mut animations = std::mem::take(&mut self.animations);
+		for animation in animations.iter_mut() {
+			animation.elapsed += seconds;
+			let amount = if animation.duration > 0.0 { (animation.elapsed / animation.duration).min(1.0) } else { 1.0 };
+			if let Some(effect) = animation.from.lerp(&animation.to, amount) {
+				self.set_effect(EffectChainId(animation.chain), animation.index, effect);
+			}
+		}
+		animations.retain(|animation| animation.elapsed < animation.duration);
+		self.animations = animations;
+	}
+	
+}
+
+
+extern "C" { fn AttachAudioMixedProcessor(processor: AudioCallback); }
+extern "C" { fn DetachAudioMixedProcessor(processor: AudioCallback); }
@@ -1,7 +1,32 @@
 
 
+use std::fmt::Display;
+
 use crate::sound::*;
+use super::synth::*;
+
 
+/// #### MidiError
+/// Errors that can occur while loading a Midi track from JSON.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MidiError {
+	FailedToOpen,
+	NotJson,
+	/// The JSON isn't a Midi track, or one of its instruments failed to load.
+	Malformed,
+	/// There's no track with that number.
+	NoSuchTrack(usize),
+}
+impl Display for MidiError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			MidiError::FailedToOpen	=> write!(f, "Failed to open Midi file."),
+			MidiError::NotJson		=> write!(f, "Midi file is not JSON."),
+			MidiError::Malformed	=> write!(f, "Midi file is malformed or an instrument failed to load."),
+			MidiError::NoSuchTrack(track)	=> write!(f, "There is no Midi track {track}."),
+		}
+	}
+}
 
 #[derive(Debug, Clone, Default)]
 pub enum AudioMode {
@@ -12,18 +37,350 @@ pub enum AudioMode {
 
 /// #### Music
 /// Handles each individual Midi track.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone)]
 pub struct Midi {
 	pub is_on: bool,
 	pub looping: bool,
 	pub bpm: u32,
+	pub ticks_per_beat: u32,
+	/// Tempo changes as the tick they happen at and microseconds per beat. Before the first one the track plays at bpm.
+	pub tempo: Vec<(u32, u32)>,
+	pub beats_per_bar: u32,
+	/// Named markers as their name and the tick they're at.
+	pub markers: Vec<(String, u32)>,
 	pub instruments: Vec<Sound>,
+	/// Pool of voices for each instrument, so its notes can overlap.
+	pub voices: Vec<Vec<Voice>>,
+	/// Seconds notes take to fade out after their duration.
+	pub release: f32,
+	/// Synthesizer playing the instruments that are synths rather than samples.
+	pub synth: Option<Synthesizer>,
+	
+	//* Mixing */
+	/// Volume of the track, from 0.0 to 1.0.
+	pub volume: f32,
+	/// Pan of the track, added to the pan of each note. 0.5 leaves them as they are.
+	pub pan: f32,
+	/// Muted tracks keep playing silently, staying in time with the others.
+	pub muted: bool,
+	/// When any track is soloed only soloed tracks are heard.
+	pub solo: bool,
+	/// Paused tracks hold their position and notes until resumed.
+	pub paused: bool,
+	/// Whether the track is heard as a layer of adaptive music.
+	pub enabled: bool,
+	/// Seconds the track takes to fade in or out when enabled or disabled.
+	pub fade: f32,
+	/// Current level of the layer, fading towards 1.0 when enabled and 0.0 when disabled.
+	pub level: f32,
 	pub track: Vec<(Note, u32)>,
-	pub timer: u32,
+	/// Current position in ticks.
+	pub tick: f64,
 	pub end: u32,
 	pub track_position: usize,
 	pub length: usize,
 }
+impl Default for Midi {
+	fn default() -> Self {
+		Self {
+			is_on: false,
+			looping: false,
+			bpm: 0,
+			ticks_per_beat: 0,
+			tempo: Vec::new(),
+			beats_per_bar: 4,
+			markers: Vec::new(),
+			instruments: Vec::new(),
+			voices: Vec::new(),
+			release: 0.0,
+			synth: None,
+			volume: 1.0,
+			pan: 0.5,
+			muted: false,
+			solo: false,
+			paused: false,
+			enabled: true,
+			fade: 0.0,
+			level: 1.0,
+			track: Vec::new(),
+			tick: 0.0,
+			end: 0,
+			track_position: 0,
+			length: 0,
+		}
+	}
+}
+impl Midi {
+	
+	/// #### from_json
+	/// Builds a Midi track from its JSON description, loading each instrument by name with the input loader.
+	/// 
+	/// Notes are `[id, duration, pitch, pan]` with an optional volume, "ticks_per_beat" defaults to 4 and "release" to 0.
+	/// "beats_per_bar" defaults to 4, and "markers" are optional `[name, tick]` pairs.
+	/// Instruments given as objects instead of names are synths, see Synth::from_json.
+	/// 
+	/// Returns None if the JSON is malformed or an instrument fails to load.
+	pub fn from_json(value: &serde_json::Value, mut load_instrument: impl FnMut(&str) -> Option<Sound>) -> Option<Self> {
+		let track_data = value["data"].as_array()?;
+		let length = track_data.len();
+		
+		let mut instruments: Vec<Sound> = Vec::new();
+		let mut synths: Vec<Option<Synth>> = Vec::new();
+		for i in value["instruments"].as_array()? {
+			if i.is_object() {
+				instruments.push(Sound::default());
+				synths.push(Some(Synth::from_json(i)?));
+			} else {
+				instruments.push(load_instrument(i.as_str()?)?);
+				synths.push(None);
+			}
+		}
+		
+		let mut track: Vec<(Note, u32)> = Vec::new();
+		for i in track_data {
+			let note = i[0].as_array()?;
+			track.push((
+				Note{
+					id:			note.first()?.as_i64()? as u32,
+					duration:	note.get(1)?.as_i64()? as u32,
+					pitch:		note.get(2)?.as_f64()? as f32,
+					pan:		note.get(3)?.as_f64()? as f32,
+					volume:		note.get(4).map_or(Some(1.0), |v| v.as_f64())? as f32,
+				},
+				i[1].as_i64()? as u32,
+			));
+		}
+		let end = track.last()?.1 + 1;
+		let bpm = value["bpm"].as_i64()? as u32;
+		
+		let mut markers: Vec<(String, u32)> = Vec::new();
+		for i in value["markers"].as_array().map_or(&[][..], |markers| markers.as_slice()) {
+			markers.push((i[0].as_str()?.to_string(), i[1].as_u64()? as u32));
+		}
+		
+		Some(Self {
+			is_on: true,
+			looping: value["looping"].as_bool()?,
+			bpm,
+			ticks_per_beat: value["ticks_per_beat"].as_u64().unwrap_or(4) as u32,
+			tempo: Vec::new(),
+			beats_per_bar: value["beats_per_bar"].as_u64().unwrap_or(4) as u32,
+			markers,
+			instruments,
+			voices: Vec::new(),
+			release: value["release"].as_f64().unwrap_or(0.0) as f32,
+			synth: synths.iter().any(|synth| synth.is_some()).then(|| Synthesizer::new(synths)),
+			track,
+			tick: 0.0,
+			end,
+			track_position: 0,
+			length,
+			..Default::default()
+		})
+	}
+	
+	/// #### load_voices
+	/// Gives each instrument a pool of voices aliasing its sound, replacing any it already had, and starts streaming the synth.
+	/// 
+	/// Synth instruments, and sounds that aren't ready, get no voices.
+	pub fn load_voices(&mut self, polyphony: usize) -> &mut Self {
+		self.unload_voices();
+		self.voices = self.instruments.iter()
+			.map(|instrument| match instrument.ready() {
+				true => (0..polyphony).map(|_| Voice { sound: instrument.alias(), ..Default::default() }).collect(),
+				false => Vec::new(),
+			})
+			.collect();
+		if let Some(synth) = &mut self.synth { synth.start(); }
+		
+		self
+	}
+	/// #### unload_voices
+	/// Stops and unloads the voices of every instrument and the synth's stream.
+	pub fn unload_voices(&mut self) -> &mut Self {
+		for voice in self.voices.iter_mut().flatten() {
+			voice.sound.stop();
+			voice.sound.unload_alias();
+		}
+		self.voices.clear();
+		if let Some(synth) = &mut self.synth { synth.stop(); }
+		
+		self
+	}
+	/// #### update
+	/// Advances the track by the elapsed time, releasing notes that finished and playing the ones that start.
+	/// 
+	/// Notes play on a free voice of their instrument, taking over the oldest one when all are busy.
+	/// Instruments without voices play their sound directly, cutting off their previous note.
+	/// Synth instruments are played by the synth instead.
+	/// 
+	/// The volume is scaled by the track's volume and layer level, and changes apply to notes already playing.
+	pub fn update(&mut self, seconds: f32, volume: f32) -> &mut Self {
+		//* Fade the layer towards enabled or disabled */
+		let target = if self.enabled { 1.0 } else { 0.0 };
+		let step = if self.fade > 0.0 { seconds / self.fade } else { 1.0 };
+		self.level += (target - self.level).clamp(-step, step);
+		
+		let volume = if self.muted { 0.0 } else { volume * self.volume * self.level };
+		let seconds = if self.paused { 0.0 } else { seconds };
+		if let Some(synth) = &mut self.synth { synth.volume = volume; }
+		
+		//* Release finished notes */
+		for voice in self.voices.iter_mut().flatten().filter(|voice| voice.active) {
+			voice.age += seconds;
+			voice.remaining -= seconds;
+			if voice.remaining > 0.0 {
+				voice.sound.volume(voice.volume * volume);
+				continue
+			}
+			
+			let fade = if self.release > 0.0 { 1.0 + voice.remaining / self.release } else { 0.0 };
+			if fade <= 0.0 {
+				voice.sound.stop();
+				voice.active = false;
+			} else {
+				voice.sound.volume(voice.volume * volume * fade);
+			}
+		}
+		
+		//* Start new notes */
+		for position in self.advance(seconds) {
+			let (note, tick) = self.track[position].clone();
+			let id = note.id as usize;
+			let Some(instrument) = self.instruments.get(id) else { continue };
+			let duration = note.duration as f32 * self.tempo_at(tick as f64) as f32 / 1_000_000.0 / self.ticks_per_beat.max(1) as f32;
+			let pan = (note.pan + self.pan - 0.5).clamp(0.0, 1.0);
+			
+			if let Some(synth) = self.synth.as_mut().filter(|synth| synth.plays(id)) {
+				synth.note_on(id, note.pitch, note.volume, pan, duration);
+				continue
+			}
+			
+			let Some(voices) = self.voices.get_mut(id).filter(|voices| !voices.is_empty()) else {
+				instrument.pitch(note.pitch).pan(pan).play();
+				continue
+			};
+			let voice = match voices.iter().position(|voice| !voice.active) {
+				Some(free) => &mut voices[free],
+				None => voices.iter_mut().max_by(|a, b| a.age.total_cmp(&b.age)).unwrap(),
+			};
+			
+			*voice = Voice {
+				sound: voice.sound,
+				active: true,
+				remaining: duration,
+				age: 0.0,
+				volume: note.volume,
+			};
+			voice.sound.stop();
+			voice.sound
+				.volume(note.volume * volume)
+				.pitch(note.pitch)
+				.pan(pan)
+				.play();
+		}
+		if let Some(synth) = &mut self.synth { synth.update(); }
+		
+		self
+	}
+	/// #### pause
+	/// Pauses the track and the notes it's playing.
+	pub fn pause(&mut self) -> &mut Self {
+		if self.paused { return self }
+		
+		self.paused = true;
+		for voice in self.voices.iter_mut().flatten().filter(|voice| voice.active) { voice.sound.pause(); }
+		if let Some(synth) = &mut self.synth { synth.pause(); }
+		
+		self
+	}
+	/// #### resume
+	/// Resumes a paused track from where it stopped.
+	pub fn resume(&mut self) -> &mut Self {
+		if !self.paused { return self }
+		
+		self.paused = false;
+		for voice in self.voices.iter_mut().flatten().filter(|voice| voice.active) { voice.sound.resume(); }
+		if let Some(synth) = &mut self.synth { synth.resume(); }
+		
+		self
+	}
+	/// #### advance
+	/// Moves the track forward by the elapsed time, returning the positions in track of the notes that start.
+	/// 
+	/// Ticks pass at the current tempo, switching at tempo changes. At the end the track loops if looping, otherwise it stops.
+	pub fn advance(&mut self, seconds: f32) -> Vec<usize> {
+		let mut notes = Vec::new();
+		if !self.is_on { return notes }
+		if self.end == 0 || self.ticks_per_beat == 0 {
+			self.is_on = false;
+			return notes
+		}
+		
+		let mut seconds = seconds as f64;
+		while seconds > 0.0 {
+			let ticks_per_second = 1_000_000.0 / self.tempo_at(self.tick) as f64 * self.ticks_per_beat as f64;
+			if !ticks_per_second.is_finite() || ticks_per_second <= 0.0 {
+				self.is_on = false;
+				break
+			}
+			
+			//* Advance up to the next tempo change or the end, whichever comes first */
+			let boundary = self.tempo.iter()
+				.map(|(tick, _)| *tick as f64)
+				.find(|tick| *tick > self.tick)
+				.unwrap_or(self.end as f64)
+				.min(self.end as f64);
+			let target = self.tick + seconds * ticks_per_second;
+			let next = target.min(boundary);
+			seconds -= (next - self.tick) / ticks_per_second;
+			self.tick = next;
+			
+			while let Some((_, tick)) = self.track.get(self.track_position) {
+				if *tick as f64 >= self.tick { break }
+				notes.push(self.track_position);
+				self.track_position += 1;
+			}
+			
+			//* End of the track */
+			if self.tick >= self.end as f64 {
+				self.tick = 0.0;
+				self.track_position = 0;
+				if !self.looping {
+					self.is_on = false;
+					break
+				}
+			}
+			if next >= target { break }
+		}
+		
+		notes
+	}
+	/// #### tempo_at
+	/// Returns the microseconds per beat at the tick.
+	pub fn tempo_at(&self, tick: f64) -> u32 {
+		self.tempo.iter()
+			.take_while(|(change, _)| *change as f64 <= tick)
+			.last()
+			.map_or(60_000_000 / self.bpm.max(1), |(_, tempo)| *tempo)
+	}
+	/// #### duration
+	/// Returns how many seconds one pass through the track takes, following its tempo changes.
+	pub fn duration(&self) -> f32 {
+		if self.ticks_per_beat == 0 { return 0.0 }
+		
+		let end = self.end as f64;
+		let mut tick = 0.0;
+		let mut seconds = 0.0;
+		for boundary in self.tempo.iter().map(|(change, _)| *change as f64).filter(|change| *change > 0.0 && *change < end).chain([end]) {
+			seconds += (boundary - tick) * self.tempo_at(tick) as f64 / 1_000_000.0 / self.ticks_per_beat as f64;
+			tick = boundary;
+		}
+		
+		seconds as f32
+	}
+	
+}
 
 /// #### Note
 /// Handles each Midi note.
@@ -33,4 +390,21 @@ pub struct Note {
 	pub duration: u32,
 	pub pitch: f32,
 	pub pan: f32,
+	pub volume: f32,
 }
+
+/// #### Voice
+/// Alias of an instrument playing a single note.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Voice {
+	pub sound: Sound,
+	pub active: bool,
+	/// Seconds the note has left before it's released.
+	pub remaining: f32,
+	/// Seconds since the note started.
+	pub age: f32,
+	pub volume: f32,
+}
+
+/// Number of voices given to each instrument by default.
+pub const POLYPHONY: usize = 8;
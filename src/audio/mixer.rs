@@ -0,0 +1,156 @@
+use std::collections::HashMap;
+
+
+/// Buses every Mixer starts with. Every other bus plays through master.
+pub const DEFAULT_BUSES: [&str; 5] = ["master", "music", "sfx", "voice", "ui"];
+
+/// #### Bus
+/// Mixer channel a group of sounds plays through.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bus {
+	/// Volume of the bus, from 0.0 to 1.0.
+	pub volume: f32,
+	pub muted: bool,
+}
+impl Default for Bus {
+	fn default() -> Self {
+		Self {
+			volume: 1.0,
+			muted: false,
+		}
+	}
+}
+
+/// #### Ducking
+/// Lowers one bus while sounds on another are playing, like music under dialogue.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ducking {
+	/// Bus whose sounds trigger the ducking.
+	pub trigger: String,
+	/// Bus that gets lowered.
+	pub target: String,
+	/// Level the target is lowered to, from 0.0 to 1.0.
+	pub level: f32,
+	/// Seconds the target takes to lower.
+	pub attack: f32,
+	/// Seconds the target takes to come back once the trigger is quiet.
+	pub release: f32,
+}
+impl Default for Ducking {
+	fn default() -> Self {
+		Self {
+			trigger: "voice".to_string(),
+			target: "music".to_string(),
+			level: 0.3,
+			attack: 0.1,
+			release: 0.5,
+		}
+	}
+}
+
+/// #### Mixer
+/// Named buses with their volumes, and the ducking between them.
+#[derive(Debug, Clone)]
+pub struct Mixer {
+	buses: HashMap<String, Bus>,
+	pub ducking: Option<Ducking>,
+	/// Current level of the ducked bus, 1.0 when it isn't ducked.
+	duck: f32,
+}
+impl Default for Mixer {
+	fn default() -> Self {
+		let mut buses: HashMap<String, Bus> = DEFAULT_BUSES.iter().map(|name| (name.to_string(), Bus::default())).collect();
+		buses.insert("master".to_string(), Bus { volume: 0.8, muted: false });
+		buses.insert("music".to_string(), Bus { volume: 0.6, muted: false });
+		buses.insert("sfx".to_string(), Bus { volume: 0.8, muted: false });
+		
+		Self {
+			buses,
+			ducking: Some(Ducking::default()),
+			duck: 1.0,
+		}
+	}
+}
+impl Mixer {
+	
+	/// #### new
+	/// Creates a Mixer with the default buses, ducking music under voice.
+	pub fn new() -> Self {
+		Self::default()
+	}
+	/// #### add_bus
+	/// Adds a bus if it doesn't exist yet, returning it.
+	pub fn add_bus(&mut self, name: &str) -> &mut Bus {
+		self.buses.entry(name.to_string()).or_default()
+	}
+	/// #### bus
+	/// Returns the bus if it exists.
+	pub fn bus(&self, name: &str) -> Option<&Bus> {
+		self.buses.get(name)
+	}
+	/// #### bus_mut
+	/// Returns the bus for changing it directly if it exists.
+	pub fn bus_mut(&mut self, name: &str) -> Option<&mut Bus> {
+		self.buses.get_mut(name)
+	}
+	/// #### buses
+	/// Returns the names of every bus, sorted.
+	pub fn buses(&self) -> Vec<String> {
+		let mut names: Vec<String> = self.buses.keys().cloned().collect();
+		names.sort();
+		
+		names
+	}
+	/// #### set_volume
+	/// Sets the volume of the bus, adding it if it doesn't exist.
+	pub fn set_volume(&mut self, name: &str, volume: f32) -> &mut Self {
+		self.add_bus(name).volume = volume;
+		
+		self
+	}
+	/// #### volume
+	/// Returns the volume of the bus, 1.0 if it doesn't exist.
+	pub fn volume(&self, name: &str) -> f32 {
+		self.buses.get(name).map_or(1.0, |bus| bus.volume)
+	}
+	/// #### mute
+	/// Mutes or unmutes the bus, adding it if it doesn't exist.
+	pub fn mute(&mut self, name: &str, muted: bool) -> &mut Self {
+		self.add_bus(name).muted = muted;
+		
+		self
+	}
+	/// #### gain
+	/// Returns the final volume of sounds on the bus, through master and any ducking.
+	pub fn gain(&self, name: &str) -> f32 {
+		let level = |name: &str| self.buses.get(name).map_or(1.0, |bus| if bus.muted { 0.0 } else { bus.volume });
+		let ducked = self.ducking.as_ref().is_some_and(|ducking| ducking.target == name);
+		
+		let gain = if name == "master" { level(name) } else { level(name) * level("master") };
+		if ducked { gain * self.duck } else { gain }
+	}
+	/// #### duck_level
+	/// Returns the current level of the ducked bus, 1.0 when it isn't ducked.
+	pub fn duck_level(&self) -> f32 {
+		self.duck
+	}
+	/// #### update
+	/// Moves the ducking towards its level while the trigger bus is playing, and back once it stops.
+	/// 
+	/// Returns whether the level changed, so the volumes of playing sounds can be updated.
+	pub fn update(&mut self, seconds: f32, triggered: bool) -> bool {
+		let Some(ducking) = &self.ducking else {
+			let changed = self.duck != 1.0;
+			self.duck = 1.0;
+			return changed
+		};
+		
+		let (target, time) = if triggered { (ducking.level, ducking.attack) } else { (1.0, ducking.release) };
+		let step = if time > 0.0 { seconds * (1.0 - ducking.level).abs() / time } else { 1.0 };
+		let previous = self.duck;
+		self.duck += (target - self.duck).clamp(-step, step);
+		
+		self.duck != previous
+	}
+	
+}
@@ -2,10 +2,20 @@
 
 use std::{collections::HashMap, fs::File, io::Read};
 
-use crate::sound::*;
-use self::midi::*;
+use crate::{files::Archive, sound::*, vectors::*};
+use self::{beats::*, effects::*, midi::*, mixer::*, playlist::*, render::*, sfx::*, smf::*, spatial::*};
 
+pub mod beats;
+pub mod effects;
 pub mod midi;
+pub mod mixer;
+pub mod playlist;
+pub mod render;
+pub mod rng;
+pub mod sfx;
+pub mod smf;
+pub mod spatial;
+pub mod synth;
 
 
 /// #### AudioHandler
@@ -14,18 +24,33 @@ pub mod midi;
 pub struct AudioHandler {
 	mode: AudioMode,
 	
-	master_volume: f32,
-	music_volume: f32,
-	sfx_volume: f32,
+	mixer: Mixer,
 	
 	//* Normal */
 	music: Option<Music>,
+	song: Song,
+	crossfade: Option<Crossfade>,
+	playlist: Option<Playlist>,
 	
 	//* Midi */
 	tracks: [Midi; 5],
 	
 	//* Sfx */
-	sfx: HashMap<String, Sound>,
+	sfx: HashMap<String, Sfx>,
+	
+	//* Positional */
+	listener: Listener,
+	attenuation: Attenuation,
+	emitters: HashMap<EmitterId, Emitter>,
+	next_emitter: usize,
+	
+	//* Effects */
+	effects: Effects,
+	
+	//* Beats */
+	clock: BeatClock,
+	events: Vec<MusicEvent>,
+	beat_track: usize,
 }
 impl Default for AudioHandler {
 	fn default() -> Self {
@@ -33,12 +58,21 @@ impl Default for AudioHandler {
 		
 		Self {
 			mode: Default::default(),
-			master_volume:	0.8,
-			music_volume:	0.6,
-			sfx_volume:		0.8,
+			mixer: Mixer::default(),
 			music: None,
+			song: Song::default(),
+			crossfade: None,
+			playlist: None,
 			tracks: Default::default(),
 			sfx: HashMap::new(),
+			listener: Listener::default(),
+			attenuation: Attenuation::default(),
+			emitters: HashMap::new(),
+			next_emitter: 0,
+			effects: Effects::default(),
+			clock: BeatClock::default(),
+			events: Vec::new(),
+			beat_track: 0,
 		}
 	}
 }
@@ -48,165 +82,777 @@ impl AudioHandler {
 	/// #### close
 	/// Turns off raylib audio interface.
 	pub fn close(&mut self) {
+		self.effects.detach_all();
 		unsafe{ CloseAudioDevice() }
 	}
 	
 	/// #### load_midi
 	/// Load Midi tracks from file into memory.
-	pub fn load_midi(&mut self, track_name: &str, track_number: usize) -> &mut Self {
-		let file = File::open(track_name);
-		if file.is_err() { return self; }
-		
-		let dat: &mut String = &mut "".to_string();
-		let _ = file.unwrap().read_to_string(dat);
-		let dat_value: serde_json::Value = serde_json::from_str(dat).expect("Failed to convert file.");
-		
-		let track_data = dat_value.as_object().unwrap()["data"].as_array().unwrap();
-		let length = track_data.len();
-		
-		let mut instruments: Vec<Sound> = Vec::new();
-		for i in dat_value.as_object().unwrap()["instruments"].as_array().unwrap() {
-			let value = i.as_str().unwrap();
-			instruments.push(Sound::load(value));
-		}
-		
-		let mut track: Vec<(Note, u32)> = Vec::new();
-		for i in track_data {
-			let array = i.as_array().unwrap();
-			let new: (Note, u32) = (
-				Note{
-					id:			array[0].as_array().unwrap()[0].as_i64().unwrap() as u32,
-					duration:	array[0].as_array().unwrap()[1].as_i64().unwrap() as u32,
-					pitch:		array[0].as_array().unwrap()[2].as_f64().unwrap() as f32,
-					pan:		array[0].as_array().unwrap()[3].as_f64().unwrap() as f32,
-				},
-				array[1].as_i64().unwrap() as u32,
-			);
-			track.push(new);
-		}
-		let end = track_data[track_data.len()-1].as_array().unwrap()[1].as_i64().unwrap() as u32 + 1;
-		
-		self.tracks[track_number] = Midi {
-			is_on: true,
-			looping: dat_value.as_object().unwrap()["looping"].as_bool().unwrap(),
-			bpm: dat_value.as_object().unwrap()["bpm"].as_i64().unwrap() as u32,
-			instruments,
-			track,
-			timer: 0,
-			end,
-			track_position: 0,
-			length,
-		};
+	pub fn load_midi(&mut self, track_name: &str, track_number: usize) -> Result<&mut Self, MidiError> {
+		if track_number >= self.tracks.len() { return Err(MidiError::NoSuchTrack(track_number)) }
+		let mut file = File::open(track_name).map_err(|_| MidiError::FailedToOpen)?;
+		
+		let mut dat = String::new();
+		file.read_to_string(&mut dat).map_err(|_| MidiError::FailedToOpen)?;
+		let dat_value: serde_json::Value = serde_json::from_str(&dat).map_err(|_| MidiError::NotJson)?;
+		
+		let midi = Midi::from_json(&dat_value, |filename| Some(Sound::load(filename)).filter(Sound::ready)).ok_or(MidiError::Malformed)?;
+		
+		Ok(self.set_track(track_number, midi))
+	}
+	/// #### load_midi_from_archive
+	/// Load Midi tracks from an archive into memory.
+	/// 
+	/// Instruments are looked up by name in the same archive.
+	pub fn load_midi_from_archive(&mut self, archive: &(impl Archive + ?Sized), track_name: &str, track_number: usize) -> Result<&mut Self, MidiError> {
+		if track_number >= self.tracks.len() { return Err(MidiError::NoSuchTrack(track_number)) }
+		let data = archive.get(track_name).ok_or(MidiError::FailedToOpen)?;
+		let dat_value: serde_json::Value = serde_json::from_slice(&data).map_err(|_| MidiError::NotJson)?;
+		
+		let midi = Midi::from_json(&dat_value, |name| archive.get_sound(name)).ok_or(MidiError::Malformed)?;
+		
+		Ok(self.set_track(track_number, midi))
+	}
+	/// #### load_smf
+	/// Load Midi tracks from a Standard MIDI File into memory, playing its notes with the input instruments.
+	pub fn load_smf(&mut self, filename: &str, track_number: usize, instruments: &[SmfInstrument]) -> Result<&mut Self, SmfError> {
+		let data = std::fs::read(filename).map_err(|_| SmfError::FailedToOpen)?;
+		let midi = Midi::from_smf(&data, instruments)?;
+		
+		Ok(self.set_track(track_number, midi))
+	}
+	/// #### set_mode
+	/// Switches between playing the song and the Midi tracks.
+	pub fn set_mode(&mut self, mode: AudioMode) -> &mut Self {
+		self.mode = mode;
+		self.sync_beats();
+		
+		self.update_volume()
+	}
+	/// #### track
+	/// Returns the Midi track.
+	pub fn track(&self, track_number: usize) -> &Midi {
+		&self.tracks[track_number]
+	}
+	/// #### track_mut
+	/// Returns the Midi track for changing it directly.
+	pub fn track_mut(&mut self, track_number: usize) -> &mut Midi {
+		&mut self.tracks[track_number]
+	}
+	/// #### set_polyphony
+	/// Sets how many notes each instrument of the Midi track can play at once.
+	pub fn set_polyphony(&mut self, track_number: usize, polyphony: usize) -> &mut Self {
+		self.tracks[track_number].load_voices(polyphony);
 		
 		self
 	}
+	/// #### render_tracks
+	/// Creates a Renderer of every loaded Midi track, with their mixing, to render or export them offline.
+	/// 
+	/// Samples holds the Samples of each track's instruments by track number, as Raylib's Sounds can't be read back.
+	pub fn render_tracks(&self, sample_rate: u32, mut samples: Vec<Vec<Option<Sample>>>) -> Renderer {
+		samples.resize(self.tracks.len(), Vec::new());
+		
+		let mut renderer = Renderer::new(sample_rate);
+		for (track, samples) in self.tracks.iter().zip(samples).filter(|(track, _)| !track.track.is_empty()) {
+			renderer.add_track(track, samples);
+		}
+		
+		renderer
+	}
 	/// #### load_song
 	/// Load song from file into memory.
 	pub fn load_song(&mut self, filename: &str) -> &mut Self {
-		if self.music.is_some() {
-			if self.music.unwrap().playing() { self.music.unwrap().stop(); }
-			self.music.unwrap().unload();
+		self.play_song(Song::new(filename), 0.0)
+	}
+	/// #### load_song_from_archive
+	/// Load song from an archive into memory.
+	pub fn load_song_from_archive(&mut self, archive: &(impl Archive + ?Sized), name: &str) -> &mut Self {
+		let Some(music) = archive.get_music(name) else { return self };
+		
+		self.set_music(music, Song { filename: name.to_string(), ..Default::default() }, 0.0)
+	}
+	/// #### crossfade_song
+	/// Load song from file into memory, crossfading into it from the current one over the seconds.
+	pub fn crossfade_song(&mut self, filename: &str, fade: f32) -> &mut Self {
+		self.play_song(Song::new(filename), fade)
+	}
+	/// #### play_song
+	/// Load song from file into memory, crossfading into it over the seconds and looping its loop region if it has one.
+	pub fn play_song(&mut self, song: Song, fade: f32) -> &mut Self {
+		let music = Music::load(&song.filename);
+		
+		self.set_music(music, song, fade)
+	}
+	/// #### song
+	/// Returns the song being played.
+	pub fn song(&self) -> &Song {
+		&self.song
+	}
+	
+	//= Playlists
+	/// #### play_playlist
+	/// Starts playing the playlist from its first song.
+	/// 
+	/// Songs crossfade into the next one as they end. Songs with a loop region keep looping until next_song is called.
+	pub fn play_playlist(&mut self, mut playlist: Playlist) -> &mut Self {
+		let first = playlist.start().cloned();
+		let fade = playlist.fade;
+		self.playlist = Some(playlist);
+		
+		match first {
+			Some(song) => self.play_song(song, fade),
+			None => self,
+		}
+	}
+	/// #### next_song
+	/// Crossfades into the next song of the playlist, stopping the music once a playlist that doesn't repeat is over.
+	pub fn next_song(&mut self) -> &mut Self {
+		let Some(playlist) = &mut self.playlist else { return self };
+		let fade = playlist.fade;
+		
+		match playlist.next_song().cloned() {
+			Some(song) => self.play_song(song, fade),
+			None => self.stop_song(),
+		}
+	}
+	/// #### previous_song
+	/// Crossfades into the previous song of the playlist.
+	pub fn previous_song(&mut self) -> &mut Self {
+		let Some(playlist) = &mut self.playlist else { return self };
+		let fade = playlist.fade;
+		
+		match playlist.previous_song().cloned() {
+			Some(song) => self.play_song(song, fade),
+			None => self,
+		}
+	}
+	/// #### stop_playlist
+	/// Forgets the playlist, letting the current song keep playing.
+	pub fn stop_playlist(&mut self) -> &mut Self {
+		self.playlist = None;
+		
+		self
+	}
+	/// #### playlist
+	/// Returns the playlist being played.
+	pub fn playlist(&self) -> Option<&Playlist> {
+		self.playlist.as_ref()
+	}
+	/// #### playlist_mut
+	/// Returns the playlist being played for changing it directly.
+	pub fn playlist_mut(&mut self) -> Option<&mut Playlist> {
+		self.playlist.as_mut()
+	}
+	/// #### stop_song
+	/// Stops and unloads the current song and any song fading out.
+	pub fn stop_song(&mut self) -> &mut Self {
+		if let Some(from) = self.crossfade.take().and_then(|crossfade| crossfade.from) {
+			self.effects.detach_stream(from.stream());
+			from.unload();
+		}
+		if let Some(mut music) = self.music.take() {
+			self.effects.detach_stream(music.stream());
+			music.stop();
+			music.unload();
 		}
+		self.song = Song::default();
+		
+		self.attach_effects()
+	}
+	/// #### load_sfx_from_archive
+	/// Loads sfx from an archive into hashmap for future use.
+	pub fn load_sfx_from_archive(&mut self, archive: &(impl Archive + ?Sized), entry: &str, name: &str) -> &mut Self {
+		let Some(sound) = archive.get_sound(entry) else { return self };
 		
-		self.music = Some(Music::load(filename));
-		self.music.unwrap()
-			.volume(self.master_volume * self.music_volume)
-			.play();
+		self.add_sfx(name, sound, "sfx")
+	}
+	
+	/// #### set_track
+	/// Replaces the Midi track, giving its instruments voices.
+	fn set_track(&mut self, track_number: usize, mut midi: Midi) -> &mut Self {
+		self.tracks[track_number].unload_voices();
+		midi.load_voices(POLYPHONY);
+		self.tracks[track_number] = midi;
+		if track_number == self.beat_track { self.clock.restart(); }
+		
+		self
+	}
+	/// #### set_music
+	/// Replaces the current song and starts playing it, crossfading over the seconds.
+	fn set_music(&mut self, mut music: Music, song: Song, fade: f32) -> &mut Self {
+		if fade <= 0.0 {
+			self.stop_song();
+		} else {
+			//* A song still fading out is cut off by the new crossfade */
+			if let Some(from) = self.crossfade.take().and_then(|crossfade| crossfade.from) {
+				self.effects.detach_stream(from.stream());
+				from.unload();
+			}
+			self.crossfade = Some(Crossfade::new(self.music.take(), fade));
+		}
+		
+		//* Songs with a loop region, and songs in a playlist, end instead of starting over */
+		music.looping(!song.has_loop() && self.playlist.is_none());
+		self.music = Some(music);
+		self.song = song;
+		self.clock.restart();
+		
+		self.update_volume();
+		self.attach_effects();
+		if let Some(mut music) = self.music { music.play(); }
+		
+		self
+	}
+	/// #### update_song
+	/// Advances the crossfade, loops the song's loop region and moves on through the playlist.
+	fn update_song(&mut self, delta: f32) -> &mut Self {
+		let Some(mut music) = self.music else { return self };
+		music.update();
+		
+		//* Crossfade */
+		if let Some(crossfade) = &mut self.crossfade {
+			crossfade.elapsed += delta;
+			if let Some(mut from) = crossfade.from { from.update(); }
+			if crossfade.done() {
+				let from = crossfade.from;
+				self.crossfade = None;
+				if let Some(mut from) = from {
+					self.effects.detach_stream(from.stream());
+					from.stop();
+					from.unload();
+					self.attach_effects();
+				}
+			}
+			self.update_volume();
+		}
+		
+		//* Loop region */
+		if self.song.has_loop() {
+			let (played, length) = (music.played(), music.length());
+			if let Some(position) = self.song.loop_seek(played, length) {
+				music.seek(position);
+			} else if !music.playing() {
+				music.play().seek(self.song.loop_start.unwrap_or(0.0));
+			}
+			return self
+		}
+		
+		//* Playlist, crossfading into the next song as this one ends */
+		let Some(playlist) = &self.playlist else { return self };
+		let ending = music.length() - music.played() <= playlist.fade;
+		if self.crossfade.is_none() && (ending || !music.playing()) { self.next_song(); }
 		
 		self
 	}
 	/// #### load_sfx
-	/// Loads sfx into hashmap for future use.
+	/// Loads sfx into hashmap for future use, playing through the sfx bus.
 	pub fn load_sfx(&mut self, filename: &str, name: &str) -> &mut Self {
-		let mut sound = Sound::load(filename);
-		sound.volume(self.master_volume * self.sfx_volume);
-		self.sfx.insert(name.to_string(), sound);
+		self.load_sfx_on_bus(filename, name, "sfx")
+	}
+	/// #### load_sfx_on_bus
+	/// Loads sfx into hashmap for future use, playing through the input bus.
+	pub fn load_sfx_on_bus(&mut self, filename: &str, name: &str, bus: &str) -> &mut Self {
+		let sound = Sound::load(filename);
+		
+		self.add_sfx(name, sound, bus)
+	}
+	/// #### set_sfx_bus
+	/// Moves a loaded sfx to another bus.
+	pub fn set_sfx_bus(&mut self, name: &str, bus: &str) -> &mut Self {
+		let Some(sfx) = self.sfx.get_mut(name) else { return self };
+		sfx.bus = bus.to_string();
+		
+		self.attach_effects();
+		self.update_volume()
+	}
+	/// #### sfx_bus
+	/// Returns the bus a loaded sfx plays through.
+	pub fn sfx_bus(&self, name: &str) -> Option<&str> {
+		self.sfx.get(name).map(|sfx| sfx.bus.as_str())
+	}
+	/// #### add_sfx
+	/// Stores a loaded sfx on the bus, adding the bus if it doesn't exist.
+	/// 
+	/// An sfx with the same name is unloaded, its emitters moving to the new sound. Sounds that failed to load are skipped.
+	fn add_sfx(&mut self, name: &str, mut sound: Sound, bus: &str) -> &mut Self {
+		if !sound.ready() { return self }
+		self.mixer.add_bus(bus);
+		sound.volume(self.mixer.gain(bus));
+		
+		let mut sfx = Sfx::new(sound, bus);
+		sfx.set_voices(SFX_VOICES);
+		if let Some(replaced) = self.sfx.insert(name.to_string(), sfx) {
+			for old in replaced.sounds() { self.effects.detach_stream(old.stream()); }
+			
+			//* Emitters alias the old sound, so they're moved before it's unloaded */
+			for emitter in self.emitters.values_mut().filter(|emitter| emitter.sfx == name) {
+				self.effects.detach_stream(emitter.sound.stream());
+				emitter.sound.stop();
+				emitter.sound.unload_alias();
+				emitter.sound = sound.alias();
+				if emitter.playing { emitter.sound.play(); }
+			}
+			replaced.unload();
+		}
+		
+		self.attach_effects()
+	}
+	/// #### set_sfx_voices
+	/// Sets how many triggers of the sfx can play at once.
+	pub fn set_sfx_voices(&mut self, name: &str, count: usize) -> &mut Self {
+		let Some(sfx) = self.sfx.get_mut(name) else { return self };
+		for alias in sfx.sounds().into_iter().skip(1) { self.effects.detach_stream(alias.stream()); }
+		sfx.set_voices(count);
+		
+		self.attach_effects()
+	}
+	/// #### set_sfx_policy
+	/// Sets what the sfx does when triggered while all of its voices are busy.
+	pub fn set_sfx_policy(&mut self, name: &str, policy: StealPolicy) -> &mut Self {
+		if let Some(sfx) = self.sfx.get_mut(name) { sfx.policy = policy; }
+		
+		self
+	}
+	/// #### set_sfx_variation
+	/// Sets the random change to the pitch and volume of each trigger of the sfx.
+	pub fn set_sfx_variation(&mut self, name: &str, variation: Variation) -> &mut Self {
+		if let Some(sfx) = self.sfx.get_mut(name) { sfx.variation = variation; }
 		
 		self
 	}
+	/// #### sfx
+	/// Returns the loaded sfx.
+	pub fn sfx(&self, name: &str) -> Option<&Sfx> {
+		self.sfx.get(name)
+	}
+	/// #### sfx_mut
+	/// Returns the loaded sfx for changing it directly.
+	pub fn sfx_mut(&mut self, name: &str) -> Option<&mut Sfx> {
+		self.sfx.get_mut(name)
+	}
 	
 	/// #### update
 	/// Update music process.
 	pub fn update(&mut self) -> &mut Self {
+		let delta = unsafe{ GetFrameTime() };
+		
+		//* Duck while sounds on the trigger bus are playing */
+		let triggered = self.mixer.ducking.as_ref()
+			.is_some_and(|ducking| self.sfx.values().any(|sfx| sfx.bus == ducking.trigger && sfx.playing()));
+		if self.mixer.update(delta, triggered) { self.update_volume(); }
+		self.update_emitters();
+		self.effects.update(delta);
+		
 		match self.mode {
-			AudioMode::Normal => {
-				if self.music.is_none() { return self; }
-				
-				self.music.unwrap().update();
-			}
-			AudioMode::Midi => {
-				for i in self.tracks.as_mut().into_iter() {
-					if !i.is_on { continue }
-					
-					i.timer += 1;
-					if i.timer > i.end * 8 {
-						i.timer = 0;
-						i.track_position = 0;
-					}
-					
-					loop {
-						let result = i.track.get(i.track_position);
-						if result.is_none() { break }
-						if i.track[i.track_position].1 == i.timer / 8  {
-							i.instruments[i.track[i.track_position].0.id as usize]
-								.pitch(i.track[i.track_position].0.pitch)
-								.pan(i.track[i.track_position].0.pan)
-								.play();
-							i.track_position += 1;
-						} else { break }
-					}
-				}
-			}
+			AudioMode::Normal => { self.update_song(delta); }
+			AudioMode::Midi => { self.update_tracks(delta); }
 		}
 		
+		self.update_beats()
+	}
+	/// #### update_tracks
+	/// Advances the Midi tracks, leaving only soloed ones audible when any are.
+	fn update_tracks(&mut self, delta: f32) -> &mut Self {
+		let volume = self.mixer.gain("music");
+		let soloing = self.tracks.iter().any(|track| track.solo);
+		for track in self.tracks.iter_mut() {
+			let audible = !soloing || track.solo;
+			track.update(delta, if audible { volume } else { 0.0 });
+		}
+		
+		self
+	}
+	
+	//= Track mixing
+	/// #### set_track_volume
+	/// Sets the volume of the Midi track, from 0.0 to 1.0.
+	pub fn set_track_volume(&mut self, track_number: usize, volume: f32) -> &mut Self {
+		self.tracks[track_number].volume = volume;
+		
+		self.update_volume()
+	}
+	/// #### set_track_pan
+	/// Sets the pan of the Midi track, 0.5 being the center.
+	pub fn set_track_pan(&mut self, track_number: usize, pan: f32) -> &mut Self {
+		self.tracks[track_number].pan = pan;
+		
+		self
+	}
+	/// #### mute_track
+	/// Mutes or unmutes the Midi track, which keeps playing in time with the others.
+	pub fn mute_track(&mut self, track_number: usize, muted: bool) -> &mut Self {
+		self.tracks[track_number].muted = muted;
+		
+		self.update_volume()
+	}
+	/// #### solo_track
+	/// Solos or unsolos the Midi track. While any track is soloed the others are silent.
+	pub fn solo_track(&mut self, track_number: usize, solo: bool) -> &mut Self {
+		self.tracks[track_number].solo = solo;
+		
+		self.update_volume()
+	}
+	/// #### pause_track
+	/// Pauses the Midi track where it is.
+	pub fn pause_track(&mut self, track_number: usize) -> &mut Self {
+		self.tracks[track_number].pause();
+		
+		self
+	}
+	/// #### resume_track
+	/// Resumes a paused Midi track.
+	pub fn resume_track(&mut self, track_number: usize) -> &mut Self {
+		self.tracks[track_number].resume();
+		
+		self
+	}
+	/// #### enable_track
+	/// Enables or disables the Midi track as a layer of adaptive music, fading over the seconds.
+	/// 
+	/// Disabled tracks keep playing silently, so they come back in time with the others.
+	pub fn enable_track(&mut self, track_number: usize, enabled: bool, fade: f32) -> &mut Self {
+		let track = &mut self.tracks[track_number];
+		track.enabled = enabled;
+		track.fade = fade;
+		
 		self
 	}
 	/// #### set_master_volume
 	/// 
 	pub fn set_master_volume(&mut self, volume: f32) -> &mut Self {
-		self.master_volume = volume;
-		
-		self.update_volume()
+		self.set_bus_volume("master", volume)
 	}
 	/// #### set_music_volume
 	/// 
 	pub fn set_music_volume(&mut self, volume: f32) -> &mut Self {
-		self.music_volume = volume;
-		
-		self.update_volume()
+		self.set_bus_volume("music", volume)
 	}
 	/// #### set_sfx_volume
 	/// 
 	pub fn set_sfx_volume(&mut self, volume: f32) -> &mut Self {
-		self.sfx_volume = volume;
+		self.set_bus_volume("sfx", volume)
+	}
+	
+	//= Buses
+	/// #### add_bus
+	/// Adds a bus that sfx can be loaded on, playing through master.
+	pub fn add_bus(&mut self, name: &str) -> &mut Self {
+		self.mixer.add_bus(name);
+		
+		self
+	}
+	/// #### set_bus_volume
+	/// Sets the volume of the bus, applying it to everything playing through it right away.
+	pub fn set_bus_volume(&mut self, name: &str, volume: f32) -> &mut Self {
+		self.mixer.set_volume(name, volume);
 		
 		self.update_volume()
 	}
+	/// #### bus_volume
+	/// Returns the volume of the bus.
+	pub fn bus_volume(&self, name: &str) -> f32 {
+		self.mixer.volume(name)
+	}
+	/// #### mute_bus
+	/// Mutes or unmutes the bus.
+	pub fn mute_bus(&mut self, name: &str, muted: bool) -> &mut Self {
+		self.mixer.mute(name, muted);
+		
+		self.update_volume()
+	}
+	/// #### set_ducking
+	/// Sets which bus is lowered while another plays, or turns ducking off with None.
+	pub fn set_ducking(&mut self, ducking: Option<Ducking>) -> &mut Self {
+		self.mixer.ducking = ducking;
+		
+		self
+	}
+	/// #### mixer
+	/// Returns the mixer holding the buses.
+	pub fn mixer(&self) -> &Mixer {
+		&self.mixer
+	}
 	/// #### update_volume
 	/// Applies changes to the volumes to the music/sfx.
 	fn update_volume(&mut self) -> &mut Self {
 		match self.mode {
 			AudioMode::Normal => {
-				if self.music.is_none() { return self; }
-				
-				self.music.unwrap().volume(self.master_volume * self.music_volume);
-			}
-			AudioMode::Midi => {
-				// TODO
+				let gain = self.mixer.gain("music");
+				let (out, into) = self.crossfade.map_or((0.0, 1.0), |crossfade| crossfade.levels());
+				if let Some(mut music) = self.music { music.volume(gain * into); }
+				if let Some(mut from) = self.crossfade.and_then(|crossfade| crossfade.from) { from.volume(gain * out); }
 			}
+			AudioMode::Midi => { self.update_tracks(0.0); }
 		}
 		
-		// TODO SFX
+		for sfx in self.sfx.values_mut() { sfx.apply_gain(self.mixer.gain(&sfx.bus)); }
 		
-		self
+		self.update_emitters()
 	}
 	/// #### play_sfx
-	/// Plays the input sfx if it exists in memory.
+	/// Plays the input sfx if it exists in memory, on a free voice or one stolen by its policy.
 	pub fn play_sfx(&mut self, name: &str) -> &mut Self {
-		let result = self.sfx.get(name);
+		let result = self.sfx.get_mut(name);
 		if result.is_none() { return self; }
 		
-		result.unwrap().play();
+		let sfx = result.unwrap();
+		sfx.trigger(self.mixer.gain(&sfx.bus), 1.0, 0.5);
+		
+		self
+	}
+	
+	//= Positional audio
+	/// #### play_sfx_at
+	/// Plays the input sfx if it exists in memory, with the volume and pan heard by the listener from the position.
+	pub fn play_sfx_at(&mut self, name: &str, position: Vector3) -> &mut Self {
+		let Some(sfx) = self.sfx.get_mut(name) else { return self };
+		
+		let (volume, pan) = self.listener.spatialize(position, &self.attenuation);
+		sfx.trigger(self.mixer.gain(&sfx.bus), volume, pan);
+		
+		self
+	}
+	/// #### set_listener
+	/// Sets where positioned sounds are heard from. Pleroma sets it from its camera every frame.
+	pub fn set_listener(&mut self, listener: Listener) -> &mut Self {
+		self.listener = listener;
+		
+		self
+	}
+	/// #### listener
+	/// Returns where positioned sounds are heard from.
+	pub fn listener(&self) -> &Listener {
+		&self.listener
+	}
+	/// #### set_attenuation
+	/// Sets the rolloff and distances of play_sfx_at and of emitters added afterwards.
+	pub fn set_attenuation(&mut self, attenuation: Attenuation) -> &mut Self {
+		self.attenuation = attenuation;
+		
+		self
+	}
+	/// #### add_emitter
+	/// Adds an emitter playing the input sfx from the position, returning None if the sfx isn't in memory or didn't load.
+	/// 
+	/// Emitters play their own alias of the sfx, so several can play it at once. They start stopped.
+	pub fn add_emitter(&mut self, name: &str, position: Vector3, looping: bool) -> Option<EmitterId> {
+		let sfx = self.sfx.get(name).filter(|sfx| sfx.sound.ready())?;
+		let id = EmitterId(self.next_emitter);
+		self.next_emitter += 1;
+		
+		self.emitters.insert(id, Emitter {
+			sound: sfx.sound.alias(),
+			sfx: name.to_string(),
+			bus: sfx.bus.clone(),
+			position,
+			attenuation: self.attenuation,
+			looping,
+			volume: 1.0,
+			playing: false,
+		});
+		self.attach_effects();
+		
+		Some(id)
+	}
+	/// #### play_emitter
+	/// Starts the emitter's sound from the beginning.
+	pub fn play_emitter(&mut self, id: EmitterId) -> &mut Self {
+		let Some(emitter) = self.emitters.get_mut(&id) else { return self };
+		emitter.playing = true;
+		emitter.sound.play();
+		emitter.update(&self.listener, self.mixer.gain(&emitter.bus));
+		
+		self
+	}
+	/// #### stop_emitter
+	/// Stops the emitter's sound.
+	pub fn stop_emitter(&mut self, id: EmitterId) -> &mut Self {
+		let Some(emitter) = self.emitters.get_mut(&id) else { return self };
+		emitter.playing = false;
+		emitter.sound.stop();
+		
+		self
+	}
+	/// #### move_emitter
+	/// Moves the emitter to the position.
+	pub fn move_emitter(&mut self, id: EmitterId, position: Vector3) -> &mut Self {
+		if let Some(emitter) = self.emitters.get_mut(&id) { emitter.position = position; }
+		
+		self
+	}
+	/// #### emitter
+	/// Returns the emitter.
+	pub fn emitter(&self, id: EmitterId) -> Option<&Emitter> {
+		self.emitters.get(&id)
+	}
+	/// #### emitter_mut
+	/// Returns the emitter for changing it directly.
+	pub fn emitter_mut(&mut self, id: EmitterId) -> Option<&mut Emitter> {
+		self.emitters.get_mut(&id)
+	}
+	/// #### remove_emitter
+	/// Stops the emitter and unloads its alias.
+	pub fn remove_emitter(&mut self, id: EmitterId) -> &mut Self {
+		let Some(mut emitter) = self.emitters.remove(&id) else { return self };
+		self.effects.detach_stream(emitter.sound.stream());
+		emitter.sound.stop();
+		emitter.sound.unload_alias();
+		
+		self.attach_effects()
+	}
+	/// #### update_emitters
+	/// Applies the volume and pan heard by the listener to every emitter.
+	fn update_emitters(&mut self) -> &mut Self {
+		for emitter in self.emitters.values_mut() { emitter.update(&self.listener, self.mixer.gain(&emitter.bus)); }
+		
+		self
+	}
+	
+	//= Effects
+	/// #### add_effects
+	/// Adds a chain of the effects attached to the target, returning it to change or animate later.
+	/// 
+	/// Effects on a bus run on every sfx voice and emitter of the bus separately, each using up one of MAX_PROCESSORS slots.
+	/// Nothing is added if there aren't enough slots left for the target.
+	pub fn add_effects(&mut self, effects: Vec<Effect>, target: Target) -> Result<EffectChainId, EffectError> {
+		let id = self.effects.add(effects);
+		if let Err(error) = self.attach_effects_to(id, target) {
+			self.remove_effects(id);
+			return Err(error)
+		}
+		
+		Ok(id)
+	}
+	/// #### attach_effects_to
+	/// Moves the effect chain to the target.
+	/// 
+	/// Returns an error if there aren't enough slots left for the target, leaving the chain on the streams it got a slot for.
+	/// Streams left out get the chain once slots free up, see Effects::missing.
+	pub fn attach_effects_to(&mut self, id: EffectChainId, target: Target) -> Result<&mut Self, EffectError> {
+		self.effects.set_target(id, Some(target));
+		self.attach_effects();
+		
+		if self.effects.missing(id) > 0 { return Err(EffectError::OutOfSlots) }
+		Ok(self)
+	}
+	/// #### detach_effects
+	/// Takes the effect chain off its target, keeping it to attach again later.
+	pub fn detach_effects(&mut self, id: EffectChainId) -> &mut Self {
+		self.effects.set_target(id, None);
+		
+		self.attach_effects()
+	}
+	/// #### remove_effects
+	/// Takes the effect chain off its target and forgets it, giving its slots to chains that were left without one.
+	pub fn remove_effects(&mut self, id: EffectChainId) -> &mut Self {
+		self.effects.remove(id);
+		
+		self.attach_effects()
+	}
+	/// #### set_effect
+	/// Replaces the effect at the index of the chain right away.
+	pub fn set_effect(&mut self, id: EffectChainId, index: usize, effect: Effect) -> &mut Self {
+		self.effects.set_effect(id, index, effect);
+		
+		self
+	}
+	/// #### animate_effect
+	/// Moves the parameters of the effect at the index of the chain towards the target's over the seconds.
+	/// 
+	/// The target has to be the same kind of effect, like a LowPass closing to muffle sound underwater.
+	pub fn animate_effect(&mut self, id: EffectChainId, index: usize, target: Effect, seconds: f32) -> &mut Self {
+		self.effects.animate(id, index, target, seconds);
+		
+		self
+	}
+	/// #### effects
+	/// Returns the effect chains.
+	pub fn effects(&self) -> &Effects {
+		&self.effects
+	}
+	/// #### attach_effects
+	/// Syncs every effect chain with the streams of its target as they are now.
+	fn attach_effects(&mut self) -> &mut Self {
+		for (id, target) in self.effects.targets() {
+			let streams: Vec<Option<AudioStream>> = match &target {
+				Target::Music => self.music.into_iter()
+					.chain(self.crossfade.and_then(|crossfade| crossfade.from))
+					.map(|music| Some(music.stream()))
+					.collect(),
+				Target::Bus(bus) => self.sfx.values()
+					.filter(|sfx| &sfx.bus == bus)
+					.flat_map(|sfx| sfx.sounds())
+					.chain(self.emitters.values().filter(|emitter| &emitter.bus == bus).map(|emitter| emitter.sound))
+					.map(|sound| Some(sound.stream()))
+					.collect(),
+				Target::Master => vec![None],
+			};
+			self.effects.sync(id, &streams);
+		}
+		
+		self
+	}
+	
+	//= Beats
+	/// #### events
+	/// Returns the beats, bars and markers the music passed during the last update, in order.
+	/// 
+	/// Songs need a tempo to send them, see Song::with_tempo. In Midi mode they come from the beat track.
+	pub fn events(&self) -> &[MusicEvent] {
+		&self.events
+	}
+	/// #### beat
+	/// Returns the position of the music in beats, or None if it doesn't have beats.
+	pub fn beat(&self) -> Option<f64> {
+		self.beat_source().map(|(position, _)| position)
+	}
+	/// #### set_beat_track
+	/// Sets the Midi track whose beats and markers are sent in Midi mode.
+	pub fn set_beat_track(&mut self, track_number: usize) -> &mut Self {
+		self.beat_track = track_number;
+		
+		self.sync_beats()
+	}
+	/// #### beat_track
+	/// Returns the Midi track whose beats and markers are sent in Midi mode.
+	pub fn beat_track(&self) -> usize {
+		self.beat_track
+	}
+	/// #### beat_source
+	/// Returns the position in beats and the beat map of the music of the current mode.
+	fn beat_source(&self) -> Option<(f64, BeatMap)> {
+		match self.mode {
+			AudioMode::Normal => {
+				let mut music = self.music?;
+				Some((self.song.beat(music.played())?, self.song.beat_map(music.length())?))
+			}
+			AudioMode::Midi => {
+				let track = self.tracks.get(self.beat_track).filter(|track| track.is_on)?;
+				Some((track.beat(), track.beat_map()))
+			}
+		}
+	}
+	/// #### sync_beats
+	/// Moves the beat clock to where the music is without sending what it already played.
+	fn sync_beats(&mut self) -> &mut Self {
+		match self.beat_source() {
+			Some((position, _)) => { self.clock.sync(position); }
+			None => { self.clock.restart(); }
+		}
+		
+		self
+	}
+	/// #### update_beats
+	/// Collects the events the music passed since the last update.
+	fn update_beats(&mut self) -> &mut Self {
+		self.events = match self.beat_source() {
+			Some((position, map)) => self.clock.advance(position, &map),
+			None => {
+				self.clock.restart();
+				Vec::new()
+			}
+		};
 		
 		self
 	}
@@ -217,3 +863,4 @@ impl AudioHandler {
 
 extern "C" { fn InitAudioDevice(); }
 extern "C" { fn CloseAudioDevice(); }
+extern "C" { fn GetFrameTime() -> f32; }
@@ -0,0 +1,198 @@
+use crate::sound::*;
+use super::{beats::*, rng::Rng};
+
+
+/// #### Song
+/// Music file with an optional loop region.
+/// 
+/// The part before the loop start plays once as an intro, then playback jumps back to the loop start every time it passes the loop end.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Song {
+	pub filename: String,
+	/// Seconds into the song the loop region starts, or None for a song without one.
+	pub loop_start: Option<f32>,
+	/// Seconds into the song the loop region ends, or None for the end of the song.
+	pub loop_end: Option<f32>,
+	/// Tempo the song's beats and bars are sent at, or None for a song without them.
+	pub tempo: Option<Tempo>,
+	/// Named markers as their name and seconds into the song.
+	pub markers: Vec<(String, f32)>,
+}
+impl Song {
+	
+	/// #### new
+	/// Creates a Song without a loop region.
+	pub fn new(filename: &str) -> Self {
+		Self {
+			filename: filename.to_string(),
+			..Default::default()
+		}
+	}
+	/// #### with_loop
+	/// Gives the Song a loop region, ending at the end of the song if the end is None.
+	pub fn with_loop(mut self, start: f32, end: Option<f32>) -> Self {
+		self.loop_start = Some(start);
+		self.loop_end = end;
+		
+		self
+	}
+	/// #### has_loop
+	/// Checks whether the Song has a loop region.
+	pub fn has_loop(&self) -> bool {
+		self.loop_start.is_some()
+	}
+	/// #### loop_seek
+	/// Returns where to seek to once playback has passed the loop end, keeping the time played past it.
+	pub fn loop_seek(&self, played: f32, length: f32) -> Option<f32> {
+		let start = self.loop_start?;
+		let end = self.loop_end.unwrap_or(length);
+		if end <= start || played < end { return None }
+		
+		Some(start + (played - end) % (end - start))
+	}
+	
+}
+
+/// #### Repeat
+/// What a Playlist does once its last song ends.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Repeat {
+	/// Stops after the last song.
+	Off,
+	/// Starts over from the first song, reshuffling if shuffled.
+	#[default]
+	All,
+	/// Plays the current song over and over.
+	One,
+}
+
+/// #### Playlist
+/// Songs played one after the other, crossfading between them.
+#[derive(Debug, Clone)]
+pub struct Playlist {
+	pub songs: Vec<Song>,
+	pub shuffle: bool,
+	pub repeat: Repeat,
+	/// Seconds each song crossfades into the next.
+	pub fade: f32,
+	
+	order: Vec<usize>,
+	position: usize,
+	rng: Rng,
+}
+impl Playlist {
+	
+	/// #### new
+	/// Creates a Playlist of the songs, repeating them in order.
+	pub fn new(songs: Vec<Song>) -> Self {
+		Self {
+			order: (0..songs.len()).collect(),
+			songs,
+			shuffle: false,
+			repeat: Repeat::All,
+			fade: 2.0,
+			position: 0,
+			rng: Rng::from_time(),
+		}
+	}
+	/// #### set_seed
+	/// Sets the seed used to shuffle, so the order can be reproduced.
+	pub fn set_seed(&mut self, seed: u64) -> &mut Self {
+		self.rng = Rng::new(seed);
+		
+		self
+	}
+	/// #### start
+	/// Goes back to the first song, shuffling the order if shuffled, and returns it.
+	pub fn start(&mut self) -> Option<&Song> {
+		self.reorder(None);
+		self.position = 0;
+		
+		self.current()
+	}
+	/// #### current
+	/// Returns the song being played.
+	pub fn current(&self) -> Option<&Song> {
+		self.songs.get(*self.order.get(self.position)?)
+	}
+	/// #### next_song
+	/// Moves on to the next song and returns it, or None once a playlist that doesn't repeat is over.
+	pub fn next_song(&mut self) -> Option<&Song> {
+		if self.songs.is_empty() { return None }
+		
+		match self.repeat {
+			Repeat::One => {}
+			_ if self.position + 1 < self.order.len() => self.position += 1,
+			Repeat::All => {
+				let last = self.order.last().copied();
+				self.reorder(last);
+				self.position = 0;
+			}
+			Repeat::Off => {
+				self.position = self.order.len();
+				return None
+			}
+		}
+		
+		self.current()
+	}
+	/// #### previous_song
+	/// Goes back to the previous song and returns it, staying on the first one.
+	pub fn previous_song(&mut self) -> Option<&Song> {
+		self.position = self.position.min(self.order.len()).saturating_sub(1);
+		
+		self.current()
+	}
+	
+	/// #### reorder
+	/// Rebuilds the order of the songs, shuffling them if shuffled without starting on the song that just played.
+	fn reorder(&mut self, last: Option<usize>) {
+		self.order = (0..self.songs.len()).collect();
+		if !self.shuffle { return }
+		
+		for i in (1..self.order.len()).rev() {
+			let j = self.rng.below(i as u64 + 1) as usize;
+			self.order.swap(i, j);
+		}
+		if self.order.len() > 1 && self.order.first().copied() == last {
+			let end = self.order.len() - 1;
+			self.order.swap(0, end);
+		}
+	}
+	
+}
+
+/// #### Crossfade
+/// Song fading out while the new one fades in.
+#[derive(Debug, Clone, Copy)]
+pub struct Crossfade {
+	pub from: Option<Music>,
+	pub elapsed: f32,
+	pub duration: f32,
+}
+impl Crossfade {
+	
+	/// #### new
+	/// Starts fading out of the song over the seconds.
+	pub fn new(from: Option<Music>, duration: f32) -> Self {
+		Self {
+			from,
+			elapsed: 0.0,
+			duration,
+		}
+	}
+	/// #### levels
+	/// Returns the levels of the song fading out and of the one fading in.
+	pub fn levels(&self) -> (f32, f32) {
+		if self.duration <= 0.0 { return (0.0, 1.0) }
+		
+		let progress = (self.elapsed / self.duration).clamp(0.0, 1.0);
+		(1.0 - progress, progress)
+	}
+	/// #### done
+	/// Checks whether the new song has fully faded in.
+	pub fn done(&self) -> bool {
+		self.elapsed >= self.duration
+	}
+	
+}
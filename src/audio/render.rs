@@ -0,0 +1,296 @@
+use crate::sound::*;
+use super::{midi::*, synth::*};
+
+
+/// Longest tail rendered after a song ends, for notes still releasing.
+pub const MAX_RENDER_TAIL: f32 = 30.0;
+
+/// #### Sample
+/// Sound of an instrument as stereo samples, which the Renderer plays instead of Raylib.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Sample {
+	/// Interleaved stereo samples from -1.0 to 1.0.
+	pub data: Vec<f32>,
+	pub sample_rate: u32,
+}
+impl Sample {
+	
+	/// #### from_samples
+	/// Creates a Sample from interleaved samples, doubling mono and keeping the first two channels of anything wider.
+	pub fn from_samples(samples: &[f32], sample_rate: u32, channels: u32) -> Self {
+		let channels = channels.max(1) as usize;
+		let data = samples.chunks_exact(channels)
+			.flat_map(|frame| [frame[0], frame[channels.min(2) - 1]])
+			.collect();
+		
+		Self { data, sample_rate }
+	}
+	/// #### from_wave
+	/// Creates a Sample from the Wave's samples.
+	pub fn from_wave(wave: &Wave) -> Self {
+		Self::from_samples(&wave.samples(), wave.sample_rate(), wave.channels())
+	}
+	/// #### load
+	/// Loads a Sample from an audio file.
+	pub fn load(filename: &str) -> Self {
+		let mut wave = Wave::load(filename);
+		let sample = Self::from_wave(&wave);
+		wave.unload();
+		
+		sample
+	}
+	/// #### frames
+	/// Returns the number of stereo frames in the Sample.
+	pub fn frames(&self) -> usize {
+		self.data.len() / 2
+	}
+	
+	/// #### frame
+	/// Returns the frame at the position, interpolating between the frames around it.
+	fn frame(&self, position: f64) -> [f32; 2] {
+		let index = position as usize;
+		let next = (index + 1).min(self.frames().saturating_sub(1));
+		let amount = (position - index as f64) as f32;
+		
+		[0, 1].map(|channel| {
+			let a = self.data[index * 2 + channel];
+			a + (self.data[next * 2 + channel] - a) * amount
+		})
+	}
+	
+}
+
+/// #### RenderVoice
+/// Note of a sample instrument being rendered.
+#[derive(Debug, Clone, Copy)]
+struct RenderVoice {
+	instrument: usize,
+	/// Position in the instrument's Sample in frames, and how far it moves each rendered frame.
+	position: f64,
+	step: f64,
+	volume: f32,
+	pan: f32,
+	/// Seconds the note has left before it's released.
+	remaining: f32,
+	age: f32,
+}
+
+/// #### RenderTrack
+/// Copy of a Midi track playing its instruments' Samples.
+#[derive(Debug, Clone)]
+struct RenderTrack {
+	midi: Midi,
+	samples: Vec<Option<Sample>>,
+	voices: Vec<RenderVoice>,
+	synth: Option<Synthesizer>,
+}
+impl RenderTrack {
+	
+	/// #### polyphony
+	/// Returns how many notes the instrument can play at once, as many as it has voices in the track.
+	fn polyphony(&self, instrument: usize) -> usize {
+		self.midi.voices.get(instrument).map_or(POLYPHONY, |voices| voices.len()).max(1)
+	}
+	/// #### finished
+	/// Checks whether the track has ended and every note has faded out.
+	fn finished(&self) -> bool {
+		!self.midi.is_on && self.voices.is_empty() && self.synth.as_ref().is_none_or(|synth| synth.active_voices() == 0)
+	}
+	/// #### render
+	/// Adds the next frames of the track to the interleaved stereo output, the same way Midi::update plays them.
+	fn render(&mut self, output: &mut [f32], sample_rate: u32, audible: bool) {
+		let step = 1.0 / sample_rate.max(1) as f32;
+		let level = if self.midi.enabled { 1.0 } else { 0.0 };
+		let volume = if self.midi.muted || !audible { 0.0 } else { self.midi.volume * level };
+		let frames = output.len() / 2;
+		let mut synth_frame = 0;
+		
+		for frame in 0..frames {
+			//* Start new notes */
+			for position in self.midi.advance(step) {
+				let (note, tick) = self.midi.track[position].clone();
+				let id = note.id as usize;
+				let duration = note.duration as f32 * self.midi.tempo_at(tick as f64) as f32 / 1_000_000.0 / self.midi.ticks_per_beat.max(1) as f32;
+				let pan = (note.pan + self.midi.pan - 0.5).clamp(0.0, 1.0);
+				
+				if let Some(synth) = self.synth.as_mut().filter(|synth| synth.plays(id)) {
+					//* Catch the synth up to this frame so the note starts on it */
+					Self::mix_synth(synth, &mut output[synth_frame * 2..frame * 2], volume);
+					synth_frame = frame;
+					synth.note_on(id, note.pitch, note.volume, pan, duration);
+					continue
+				}
+				
+				let Some(Some(sample)) = self.samples.get(id) else { continue };
+				if self.voices.iter().filter(|voice| voice.instrument == id).count() >= self.polyphony(id) {
+					let oldest = (0..self.voices.len())
+						.filter(|i| self.voices[*i].instrument == id)
+						.max_by(|a, b| self.voices[*a].age.total_cmp(&self.voices[*b].age))
+						.unwrap();
+					self.voices.remove(oldest);
+				}
+				self.voices.push(RenderVoice {
+					instrument: id,
+					position: 0.0,
+					step: note.pitch as f64 * sample.sample_rate as f64 / sample_rate.max(1) as f64,
+					volume: note.volume,
+					pan,
+					remaining: duration,
+					age: 0.0,
+				});
+			}
+			
+			//* Mix the voices, releasing finished notes */
+			let release = self.midi.release;
+			let samples = &self.samples;
+			self.voices.retain_mut(|voice| {
+				let Some(Some(sample)) = samples.get(voice.instrument) else { return false };
+				if voice.position >= sample.frames() as f64 { return false }
+				
+				let fade = if voice.remaining > 0.0 { 1.0 } else if release > 0.0 { 1.0 + voice.remaining / release } else { 0.0 };
+				if fade <= 0.0 { return false }
+				
+				let [left, right] = sample.frame(voice.position);
+				let gain = voice.volume * volume * fade;
+				output[frame * 2] += left * gain * (2.0 * (1.0 - voice.pan)).min(1.0);
+				output[frame * 2 + 1] += right * gain * (2.0 * voice.pan).min(1.0);
+				
+				voice.position += voice.step;
+				voice.remaining -= step;
+				voice.age += step;
+				true
+			});
+		}
+		if let Some(synth) = &mut self.synth { Self::mix_synth(synth, &mut output[synth_frame * 2..], volume); }
+	}
+	/// #### mix_synth
+	/// Adds the synth's next frames to the output, filling it.
+	fn mix_synth(synth: &mut Synthesizer, output: &mut [f32], volume: f32) {
+		if output.is_empty() { return }
+		
+		synth.volume = volume;
+		let rendered = synth.render(output.len() / 2);
+		for (sample, rendered) in output.iter_mut().zip(rendered) { *sample += rendered; }
+	}
+	
+}
+
+/// #### Renderer
+/// Renders Midi tracks into samples faster than realtime, without a sound card.
+/// 
+/// Tracks play from their start with their volume, pan, mute, solo and layers as Midi::update would, before any bus.
+/// Sample instruments play the Samples given for them, since Raylib's Sounds can't be read back, and synths play as they would live.
+/// Rendering is deterministic, so the same tracks always render the same samples.
+#[derive(Debug, Clone)]
+pub struct Renderer {
+	pub sample_rate: u32,
+	pub volume: f32,
+	
+	tracks: Vec<RenderTrack>,
+}
+impl Renderer {
+	
+	/// #### new
+	/// Creates a Renderer without tracks rendering at the sample rate.
+	pub fn new(sample_rate: u32) -> Self {
+		Self {
+			sample_rate,
+			volume: 1.0,
+			tracks: Vec::new(),
+		}
+	}
+	/// #### add_track
+	/// Adds a copy of the Midi track from its start, playing the Samples by instrument. Instruments without one are silent.
+	pub fn add_track(&mut self, midi: &Midi, samples: Vec<Option<Sample>>) -> &mut Self {
+		let mut midi = midi.clone();
+		midi.is_on = true;
+		midi.tick = 0.0;
+		midi.track_position = 0;
+		
+		let synth = midi.synth.take().map(|synth| {
+			let mut copy = Synthesizer::new(synth.instruments);
+			copy.sample_rate = self.sample_rate;
+			copy.polyphony = synth.polyphony;
+			copy
+		});
+		self.tracks.push(RenderTrack { midi, samples, voices: Vec::new(), synth });
+		
+		self
+	}
+	/// #### tracks
+	/// Returns how many tracks are being rendered.
+	pub fn tracks(&self) -> usize {
+		self.tracks.len()
+	}
+	/// #### duration
+	/// Returns how many seconds one pass through the longest track takes.
+	pub fn duration(&self) -> f32 {
+		self.tracks.iter().map(|track| track.midi.duration()).fold(0.0, f32::max)
+	}
+	/// #### finished
+	/// Checks whether every track has ended and every note has faded out. Looping tracks never end.
+	pub fn finished(&self) -> bool {
+		self.tracks.iter().all(|track| track.finished())
+	}
+	/// #### render
+	/// Renders the next frames of every track mixed together, as interleaved stereo samples from -1.0 to 1.0.
+	pub fn render(&mut self, frames: usize) -> Vec<f32> {
+		let mut samples = vec![0.0; frames * 2];
+		let soloing = self.tracks.iter().any(|track| track.midi.solo);
+		
+		for track in self.tracks.iter_mut() {
+			let audible = !soloing || track.midi.solo;
+			track.render(&mut samples, self.sample_rate, audible);
+		}
+		for sample in samples.iter_mut() { *sample = (*sample * self.volume).clamp(-1.0, 1.0); }
+		
+		samples
+	}
+	/// #### render_song
+	/// Renders the rest of every track once through, looping tracks included, until the last note has faded out.
+	pub fn render_song(&mut self) -> Vec<f32> {
+		for track in self.tracks.iter_mut() { track.midi.looping = false; }
+		
+		let block = (self.sample_rate as usize / 100).max(1);
+		let limit = ((self.duration() + MAX_RENDER_TAIL) * self.sample_rate as f32) as usize;
+		let mut samples = Vec::new();
+		while !self.finished() && samples.len() / 2 < limit {
+			let frames = block.min(limit - samples.len() / 2);
+			let rendered = self.render(frames);
+			
+			//* Stop on the frame the last note faded out on */
+			let end = if self.finished() {
+				rendered.chunks_exact(2).rposition(|frame| frame != [0.0, 0.0]).map_or(0, |last| last + 1)
+			} else {
+				frames
+			};
+			samples.extend_from_slice(&rendered[..end * 2]);
+		}
+		
+		samples
+	}
+	/// #### render_wave
+	/// Renders the next frames into a stereo 32-bit Wave.
+	pub fn render_wave(&mut self, frames: usize) -> Wave {
+		let samples = self.render(frames);
+		
+		Wave::from_samples(&samples, self.sample_rate, 2)
+	}
+	/// #### render_song_wave
+	/// Renders the rest of the song into a stereo 32-bit Wave, see render_song.
+	pub fn render_song_wave(&mut self) -> Wave {
+		let samples = self.render_song();
+		
+		Wave::from_samples(&samples, self.sample_rate, 2)
+	}
+	/// #### export_song
+	/// Renders the rest of the song and exports it to an audio file, the format following the extension.
+	pub fn export_song(&mut self, filename: &str) -> &mut Self {
+		let mut wave = self.render_song_wave();
+		wave.export(filename);
+		wave.unload();
+		
+		self
+	}
+	
+}
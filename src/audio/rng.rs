@@ -0,0 +1,43 @@
+/// #### Rng
+/// Xorshift random number generator shared by the audio modules, repeating the same numbers for the same seed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rng {
+	state: u64,
+}
+impl Rng {
+	
+	/// #### new
+	/// Creates a generator from the seed. The lowest bit is always set, as a state of zero would never change.
+	pub fn new(seed: u64) -> Self {
+		Self { state: seed | 1 }
+	}
+	/// #### from_time
+	/// Creates a generator seeded from the current time.
+	pub fn from_time() -> Self {
+		let seed = std::time::SystemTime::now()
+			.duration_since(std::time::UNIX_EPOCH)
+			.map_or(0x9E37_79B9_7F4A_7C15, |time| time.as_nanos() as u64);
+		
+		Self::new(seed)
+	}
+	/// #### next_u64
+	/// Returns the next random number.
+	pub fn next_u64(&mut self) -> u64 {
+		self.state ^= self.state << 13;
+		self.state ^= self.state >> 7;
+		self.state ^= self.state << 17;
+		
+		self.state
+	}
+	/// #### below
+	/// Returns a random number from 0 up to, but not including, the input.
+	pub fn below(&mut self, max: u64) -> u64 {
+		self.next_u64() % max
+	}
+	/// #### float
+	/// Returns a random number from 0.0 to 1.0.
+	pub fn float(&mut self) -> f32 {
+		(self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+	}
+	
+}
@@ -0,0 +1,179 @@
+use crate::sound::*;
+use super::rng::Rng;
+
+
+/// Number of voices each sfx is loaded with, so it can overlap itself.
+pub const SFX_VOICES: usize = 4;
+
+/// #### StealPolicy
+/// What an sfx does when triggered while all of its voices are busy.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum StealPolicy {
+	/// Cuts off the voice that started first.
+	#[default]
+	Oldest,
+	/// Cuts off the voice playing at the lowest volume.
+	Quietest,
+	/// Doesn't play.
+	Refuse,
+}
+impl StealPolicy {
+	
+	/// #### choose
+	/// Returns the voice to play on out of voices given as whether they're playing, when they started and their volume.
+	/// 
+	/// Free voices are always chosen first.
+	pub fn choose(&self, voices: &[(bool, u64, f32)]) -> Option<usize> {
+		if let Some(free) = voices.iter().position(|(playing, _, _)| !playing) { return Some(free) }
+		
+		match self {
+			StealPolicy::Oldest		=> (0..voices.len()).min_by_key(|i| voices[*i].1),
+			StealPolicy::Quietest	=> (0..voices.len()).min_by(|a, b| voices[*a].2.total_cmp(&voices[*b].2)),
+			StealPolicy::Refuse		=> None,
+		}
+	}
+	
+}
+
+/// #### Variation
+/// Random change to the pitch and volume of each trigger, so repeated sounds don't sound mechanical.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Variation {
+	/// Largest change in pitch either way, 0.1 playing from 0.9 to 1.1.
+	pub pitch: f32,
+	/// Largest drop in volume, 0.2 playing from 0.8 to 1.0.
+	pub volume: f32,
+}
+
+/// #### SfxVoice
+/// Sound an sfx plays one trigger on.
+#[derive(Debug, Clone, Copy)]
+struct SfxVoice {
+	sound: Sound,
+	/// Trigger the voice last played, to find the oldest.
+	started: u64,
+	/// Volume the voice plays at before its bus.
+	volume: f32,
+}
+
+/// #### Sfx
+/// Loaded sound effect with a pool of voices, and the bus it plays through.
+#[derive(Debug, Clone)]
+pub struct Sfx {
+	pub sound: Sound,
+	pub bus: String,
+	pub policy: StealPolicy,
+	pub variation: Variation,
+	
+	/// The first voice plays the sound itself, the others play aliases of it.
+	voices: Vec<SfxVoice>,
+	triggers: u64,
+	rng: Rng,
+}
+impl Sfx {
+	
+	/// #### new
+	/// Creates an sfx playing the sound through the bus with a single voice.
+	pub fn new(sound: Sound, bus: &str) -> Self {
+		Self {
+			sound,
+			bus: bus.to_string(),
+			policy: StealPolicy::Oldest,
+			variation: Variation::default(),
+			voices: vec![SfxVoice { sound, started: 0, volume: 1.0 }],
+			triggers: 0,
+			rng: Rng::from_time(),
+		}
+	}
+	/// #### set_seed
+	/// Sets the seed used for the variation, so it can be reproduced.
+	pub fn set_seed(&mut self, seed: u64) -> &mut Self {
+		self.rng = Rng::new(seed);
+		
+		self
+	}
+	/// #### set_voices
+	/// Sets how many triggers of the sfx can play at once, loading an alias for each voice past the first.
+	/// 
+	/// A sound that isn't ready can't be aliased, so it keeps a single voice.
+	pub fn set_voices(&mut self, count: usize) -> &mut Self {
+		for voice in self.voices.iter_mut().skip(1) {
+			voice.sound.stop();
+			voice.sound.unload_alias();
+		}
+		self.voices.truncate(1);
+		if !self.sound.ready() { return self }
+		for _ in 1..count.max(1) {
+			self.voices.push(SfxVoice { sound: self.sound.alias(), started: 0, volume: 1.0 });
+		}
+		
+		self
+	}
+	/// #### voices
+	/// Returns how many triggers of the sfx can play at once.
+	pub fn voices(&self) -> usize {
+		self.voices.len()
+	}
+	/// #### sounds
+	/// Returns the sound of every voice, the sfx's own sound first.
+	pub fn sounds(&self) -> Vec<Sound> {
+		self.voices.iter().map(|voice| voice.sound).collect()
+	}
+	/// #### playing
+	/// Checks whether any voice of the sfx is playing.
+	pub fn playing(&self) -> bool {
+		self.voices.iter().any(|voice| voice.sound.playing())
+	}
+	/// #### trigger
+	/// Plays the sfx at the volume and pan on a free voice, or one stolen by the policy, returning the voice used.
+	/// 
+	/// Gain is the volume of the sfx's bus.
+	pub fn trigger(&mut self, gain: f32, volume: f32, pan: f32) -> Option<usize> {
+		let states: Vec<(bool, u64, f32)> = self.voices.iter().map(|voice| (voice.sound.playing(), voice.started, voice.volume)).collect();
+		let index = self.policy.choose(&states)?;
+		let (pitch, variation) = self.next_variation();
+		self.triggers += 1;
+		
+		let voice = &mut self.voices[index];
+		voice.started = self.triggers;
+		voice.volume = volume * variation;
+		voice.sound.stop();
+		voice.sound
+			.volume(gain * voice.volume)
+			.pitch(pitch)
+			.pan(pan)
+			.play();
+		
+		Some(index)
+	}
+	/// #### next_variation
+	/// Returns a random pitch and volume within the variation.
+	pub fn next_variation(&mut self) -> (f32, f32) {
+		let pitch = 1.0 + self.variation.pitch * (self.rng.float() * 2.0 - 1.0);
+		let volume = 1.0 - self.variation.volume * self.rng.float();
+		
+		(pitch, volume.max(0.0))
+	}
+	/// #### apply_gain
+	/// Applies a new volume of the sfx's bus to every voice.
+	pub fn apply_gain(&mut self, gain: f32) -> &mut Self {
+		for voice in self.voices.iter_mut() { voice.sound.volume(gain * voice.volume); }
+		
+		self
+	}
+	/// #### stop
+	/// Stops every voice of the sfx.
+	pub fn stop(&mut self) -> &mut Self {
+		for voice in self.voices.iter_mut() { voice.sound.stop(); }
+		
+		self
+	}
+	/// #### unload
+	/// Stops and unloads every voice, the aliases before the sound they share.
+	pub fn unload(mut self) {
+		self.set_voices(1);
+		self.sound.stop();
+		self.sound.unload();
+	}
+	
+}
@@ -0,0 +1,321 @@
+use std::fmt::Display;
+
+use crate::sound::*;
+use super::{midi::*, synth::*};
+
+
+/// #### SmfInstrument
+/// Sample or synth used to play the notes of a channel or program in a Standard MIDI File.
+#[derive(Debug, Clone)]
+pub struct SmfInstrument {
+	/// Channel (0-15) the instrument plays, or None for any channel.
+	pub channel: Option<u8>,
+	/// Program (0-127) the instrument plays, or None for any program.
+	pub program: Option<u8>,
+	/// Note the sample was recorded at, which plays at a pitch of 1.0.
+	pub root_note: u8,
+	pub sound: Sound,
+	/// Synth played instead of the sound, at its frequency for the root note.
+	pub synth: Option<Synth>,
+}
+impl SmfInstrument {
+	
+	/// #### matches
+	/// Checks whether the instrument plays notes of the channel using the program.
+	fn matches(&self, channel: u8, program: u8) -> bool {
+		self.channel.is_none_or(|c| c == channel) && self.program.is_none_or(|p| p == program)
+	}
+	
+}
+
+/// #### SmfError
+/// Errors that can occur while reading a Standard MIDI File.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SmfError {
+	FailedToOpen,
+	NotSmf,
+	UnsupportedFormat(u16),
+	SmpteTiming,
+	Truncated,
+	/// Ticks go past what a u32 holds.
+	TooLong,
+}
+impl Display for SmfError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			SmfError::FailedToOpen			=> write!(f, "Failed to open MIDI file."),
+			SmfError::NotSmf				=> write!(f, "Data is not a Standard MIDI File."),
+			SmfError::UnsupportedFormat(n)	=> write!(f, "MIDI format {n} is not supported, only formats 0 and 1."),
+			SmfError::SmpteTiming			=> write!(f, "SMPTE timing is not supported, only ticks per beat."),
+			SmfError::Truncated				=> write!(f, "MIDI data ended unexpectedly."),
+			SmfError::TooLong				=> write!(f, "MIDI song is too long, its ticks overflow."),
+		}
+	}
+}
+
+/// #### Event
+/// Events from an MTrk chunk that the sequencer uses.
+#[derive(Debug, Clone, PartialEq)]
+enum Event {
+	NoteOn { channel: u8, note: u8, velocity: u8 },
+	NoteOff { channel: u8, note: u8 },
+	Program { channel: u8, program: u8 },
+	Pan { channel: u8, value: u8 },
+	Tempo(u32),
+	/// Beats per bar, counted in quarter notes.
+	TimeSignature(u32),
+	Marker(String),
+	End,
+}
+
+/// Default tempo of a MIDI file without tempo events, 120 bpm.
+const DEFAULT_TEMPO: u32 = 500_000;
+
+impl Midi {
+	
+	/// #### from_smf
+	/// Builds a Midi track from a Standard MIDI File of format 0 or 1.
+	/// 
+	/// Each note plays the first instrument matching its channel and the channel's current program, notes without one are dropped.
+	/// Pitch is the playback rate relative to the instrument's root note and pan comes from controller 10.
+	/// Beats per bar come from the first time signature, and marker events become named markers.
+	pub fn from_smf(data: &[u8], instruments: &[SmfInstrument]) -> Result<Self, SmfError> {
+		let mut reader = Reader { data, offset: 0 };
+		
+		//* Header */
+		if reader.bytes(4)? != b"MThd" { return Err(SmfError::NotSmf) }
+		let header_length = reader.u32()? as usize;
+		let format = reader.u16()?;
+		let track_count = reader.u16()?;
+		let division = reader.u16()?;
+		reader.bytes(header_length.saturating_sub(6))?;
+		
+		if format > 1 { return Err(SmfError::UnsupportedFormat(format)) }
+		if division & 0x8000 != 0 { return Err(SmfError::SmpteTiming) }
+		
+		//* Tracks, merged in order of time */
+		let mut events: Vec<(u32, Event)> = Vec::new();
+		let mut tracks_read = 0;
+		while tracks_read < track_count && reader.offset < data.len() {
+			let id = reader.bytes(4)?;
+			let length = reader.u32()? as usize;
+			let chunk = reader.bytes(length)?;
+			if id != b"MTrk" { continue }
+			
+			events.extend(read_track(chunk)?);
+			tracks_read += 1;
+		}
+		events.sort_by_key(|(tick, _)| *tick);
+		
+		//* Sequence */
+		let mut programs = [0u8; 16];
+		let mut pans = [64u8; 16];
+		let mut playing: Vec<(u8, u8, usize)> = Vec::new();
+		let mut track: Vec<(Note, u32)> = Vec::new();
+		let mut tempo: Vec<(u32, u32)> = Vec::new();
+		let mut beats_per_bar = None;
+		let mut markers: Vec<(String, u32)> = Vec::new();
+		let mut end = 0;
+		
+		for (tick, event) in events {
+			end = end.max(tick);
+			match event {
+				Event::NoteOn { channel, note, velocity } => {
+					let channel_index = channel as usize;
+					let Some(id) = instruments.iter().position(|i| i.matches(channel, programs[channel_index])) else { continue };
+					
+					playing.push((channel, note, track.len()));
+					track.push((
+						Note {
+							id: id as u32,
+							duration: 0,
+							pitch: 2f32.powf((note as f32 - instruments[id].root_note as f32) / 12.0),
+							pan: pans[channel_index] as f32 / 127.0,
+							volume: velocity as f32 / 127.0,
+						},
+						tick,
+					));
+				}
+				Event::NoteOff { channel, note } => {
+					let Some(index) = playing.iter().position(|(c, n, _)| *c == channel && *n == note) else { continue };
+					let (_, _, position) = playing.remove(index);
+					track[position].0.duration = tick - track[position].1;
+				}
+				Event::Program { channel, program }	=> programs[channel as usize] = program,
+				Event::Pan { channel, value }		=> pans[channel as usize] = value,
+				Event::Tempo(microseconds)			=> tempo.push((tick, microseconds)),
+				Event::TimeSignature(beats)			=> { beats_per_bar.get_or_insert(beats); }
+				Event::Marker(name)					=> markers.push((name, tick)),
+				Event::End => {}
+			}
+		}
+		
+		//* Notes never released last until the end */
+		for (_, _, position) in playing { track[position].0.duration = end - track[position].1; }
+		
+		let first_tempo = tempo.first().filter(|(tick, _)| *tick == 0).map_or(DEFAULT_TEMPO, |(_, tempo)| *tempo);
+		let bpm = (60_000_000.0 / first_tempo as f32).round() as u32;
+		let end = match track.last() {
+			Some((_, tick)) => end.max(tick.checked_add(1).ok_or(SmfError::TooLong)?),
+			None => end,
+		};
+		
+		Ok(Self {
+			is_on: true,
+			looping: false,
+			bpm,
+			ticks_per_beat: division as u32,
+			tempo,
+			beats_per_bar: beats_per_bar.unwrap_or(4),
+			markers,
+			instruments: instruments.iter().map(|i| i.sound).collect(),
+			voices: Vec::new(),
+			release: 0.0,
+			synth: instruments.iter().any(|i| i.synth.is_some()).then(|| Synthesizer::new(instruments.iter().map(|i| i.synth.clone()).collect())),
+			length: track.len(),
+			track,
+			tick: 0.0,
+			end,
+			track_position: 0,
+			..Default::default()
+		})
+	}
+	
+}
+
+/// #### read_track
+/// Reads the events of an MTrk chunk with their time in ticks from the start of the song.
+fn read_track(chunk: &[u8]) -> Result<Vec<(u32, Event)>, SmfError> {
+	let mut reader = Reader { data: chunk, offset: 0 };
+	let mut events = Vec::new();
+	let mut tick = 0u32;
+	let mut running_status = 0u8;
+	
+	while reader.offset < chunk.len() {
+		tick = tick.checked_add(reader.variable()?).ok_or(SmfError::TooLong)?;
+		
+		let mut status = reader.u8()?;
+		if status < 0x80 {
+			//* Running status reuses the last status, the byte read is data */
+			if running_status == 0 { return Err(SmfError::NotSmf) }
+			status = running_status;
+			reader.offset -= 1;
+		}
+		
+		match status {
+			0xFF => {
+				let kind = reader.u8()?;
+				let length = reader.variable()? as usize;
+				let data = reader.bytes(length)?;
+				match kind {
+					0x51 if length == 3 => events.push((tick, Event::Tempo(((data[0] as u32) << 16) | ((data[1] as u32) << 8) | data[2] as u32))),
+					0x58 if length >= 2 => events.push((tick, Event::TimeSignature(((data[0] as u32 * 4) >> data[1].min(31)).max(1)))),
+					0x06 => events.push((tick, Event::Marker(String::from_utf8_lossy(data).into_owned()))),
+					0x2F => {
+						events.push((tick, Event::End));
+						break
+					}
+					_ => {}
+				}
+				running_status = 0;
+			}
+			0xF0 | 0xF7 => {
+				let length = reader.variable()? as usize;
+				reader.bytes(length)?;
+				running_status = 0;
+			}
+			//* System common messages cancel running status, real-time ones leave it */
+			0xF1 | 0xF3 => {
+				reader.u8()?;
+				running_status = 0;
+			}
+			0xF2 => {
+				reader.bytes(2)?;
+				running_status = 0;
+			}
+			0xF4..=0xF6 => running_status = 0,
+			0xF8..=0xFE => {}
+			_ => {
+				running_status = status;
+				let channel = status & 0x0F;
+				match status & 0xF0 {
+					0x80 => {
+						let note = reader.u8()?;
+						reader.u8()?;
+						events.push((tick, Event::NoteOff { channel, note }));
+					}
+					0x90 => {
+						let note = reader.u8()?;
+						let velocity = reader.u8()?;
+						//* Note on with no velocity is a note off */
+						if velocity == 0 {
+							events.push((tick, Event::NoteOff { channel, note }));
+						} else {
+							events.push((tick, Event::NoteOn { channel, note, velocity }));
+						}
+					}
+					0xB0 => {
+						let controller = reader.u8()?;
+						let value = reader.u8()?;
+						if controller == 10 { events.push((tick, Event::Pan { channel, value })); }
+					}
+					0xC0 => {
+						let program = reader.u8()?;
+						events.push((tick, Event::Program { channel, program }));
+					}
+					0xD0 => { reader.u8()?; }
+					_ => { reader.bytes(2)?; }
+				}
+			}
+		}
+	}
+	
+	Ok(events)
+}
+
+/// #### Reader
+/// Reads big endian values out of MIDI data.
+struct Reader<'a> {
+	data: &'a [u8],
+	offset: usize,
+}
+impl<'a> Reader<'a> {
+	
+	/// #### bytes
+	/// Reads the next bytes.
+	fn bytes(&mut self, length: usize) -> Result<&'a [u8], SmfError> {
+		let bytes = self.data.get(self.offset..self.offset + length).ok_or(SmfError::Truncated)?;
+		self.offset += length;
+		
+		Ok(bytes)
+	}
+	/// #### u8
+	fn u8(&mut self) -> Result<u8, SmfError> {
+		Ok(self.bytes(1)?[0])
+	}
+	/// #### u16
+	fn u16(&mut self) -> Result<u16, SmfError> {
+		let bytes = self.bytes(2)?;
+		
+		Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+	}
+	/// #### u32
+	fn u32(&mut self) -> Result<u32, SmfError> {
+		let bytes = self.bytes(4)?;
+		
+		Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+	}
+	/// #### variable
+	/// Reads a variable length quantity, 7 bits per byte with the top bit set on all but the last.
+	fn variable(&mut self) -> Result<u32, SmfError> {
+		let mut value = 0u32;
+		for _ in 0..4 {
+			let byte = self.u8()?;
+			value = (value << 7) | (byte & 0x7F) as u32;
+			if byte & 0x80 == 0 { return Ok(value) }
+		}
+		
+		Err(SmfError::NotSmf)
+	}
+	
+}
@@ -0,0 +1,158 @@
+use crate::{camera::*, sound::*, vectors::*};
+
+
+/// #### Rolloff
+/// How the volume of a positioned sound falls off between its min and max distance.
+#[derive(Debug, Clone, Copy, Default)]
+pub enum Rolloff {
+	/// Falls off evenly.
+	Linear,
+	/// Falls off quickly close to the min distance then slowly, like sound in the real world.
+	#[default]
+	Inverse,
+	/// Falls off slowly close to the min distance then quickly.
+	Quadratic,
+	/// Volume from 1.0 to 0.0 for the fraction of the way from the min to the max distance.
+	Custom(fn(f32) -> f32),
+}
+
+/// #### Attenuation
+/// Volume of a positioned sound over distance from the listener.
+#[derive(Debug, Clone, Copy)]
+pub struct Attenuation {
+	pub rolloff: Rolloff,
+	/// Distance the sound plays at full volume within.
+	pub min_distance: f32,
+	/// Distance the sound can't be heard beyond.
+	pub max_distance: f32,
+}
+impl Default for Attenuation {
+	fn default() -> Self {
+		Self {
+			rolloff: Rolloff::Inverse,
+			min_distance: 1.0,
+			max_distance: 50.0,
+		}
+	}
+}
+impl Attenuation {
+	
+	/// #### gain
+	/// Returns the volume of a sound at the distance, from 1.0 to 0.0.
+	pub fn gain(&self, distance: f32) -> f32 {
+		if distance <= self.min_distance { return 1.0 }
+		if distance >= self.max_distance { return 0.0 }
+		
+		let fraction = (distance - self.min_distance) / (self.max_distance - self.min_distance);
+		let gain = match self.rolloff {
+			Rolloff::Linear		=> 1.0 - fraction,
+			Rolloff::Quadratic	=> 1.0 - fraction * fraction,
+			Rolloff::Inverse	=> {
+				//* Scaled so it reaches silence at the max distance */
+				let floor = self.min_distance.max(f32::EPSILON) / self.max_distance;
+				(self.min_distance.max(f32::EPSILON) / distance - floor) / (1.0 - floor)
+			}
+			Rolloff::Custom(curve) => curve(fraction),
+		};
+		
+		gain.clamp(0.0, 1.0)
+	}
+	
+}
+
+/// #### Listener
+/// Position and orientation sounds are heard from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Listener {
+	pub position: Vector3,
+	/// Direction to the listener's right, sounds that way pan fully right.
+	pub right: Vector3,
+}
+impl Default for Listener {
+	fn default() -> Self {
+		Self {
+			position: Vector3 { x: 0.0, y: 0.0, z: 0.0 },
+			right: Vector3 { x: 1.0, y: 0.0, z: 0.0 },
+		}
+	}
+}
+impl Listener {
+	
+	/// #### from_camera
+	/// Creates a Listener hearing from the camera.
+	/// 
+	/// In 3D it's at the camera's position facing its target, in 2D it's at the target with right following the rotation.
+	pub fn from_camera(camera: &Camera) -> Self {
+		match camera.camera_mode {
+			CameraMode::Mode2D => {
+				let angle = -camera.rotation.to_radians();
+				
+				Self {
+					position: Vector3 { x: camera.target.x, y: camera.target.y, z: 0.0 },
+					right: Vector3 { x: angle.cos(), y: angle.sin(), z: 0.0 },
+				}
+			}
+			CameraMode::Mode3D => {
+				let camera: Camera3DRl = (*camera).into();
+				let forward = camera.target - camera.position;
+				let right = forward.cross_product(camera.up).normalize();
+				
+				Self {
+					position: camera.position,
+					right,
+				}
+			}
+		}
+	}
+	/// #### spatialize
+	/// Returns the volume and pan of a sound at the position, pan going from 0.0 left to 1.0 right.
+	pub fn spatialize(&self, position: Vector3, attenuation: &Attenuation) -> (f32, f32) {
+		let offset = position - self.position;
+		let distance = offset.length();
+		let pan = if distance > 0.0 { 0.5 + 0.5 * offset.normalize().dot_product(self.right) } else { 0.5 };
+		
+		(attenuation.gain(distance), pan.clamp(0.0, 1.0))
+	}
+	
+}
+
+/// #### Emitter
+/// Long-lived positioned sound, whose volume and pan follow the listener.
+#[derive(Debug, Clone)]
+pub struct Emitter {
+	/// Alias of the sfx the emitter plays.
+	pub sound: Sound,
+	/// Name of the sfx the emitter plays.
+	pub sfx: String,
+	/// Bus the sfx plays through.
+	pub bus: String,
+	pub position: Vector3,
+	pub attenuation: Attenuation,
+	/// Looping emitters start over whenever their sound ends.
+	pub looping: bool,
+	pub volume: f32,
+	pub playing: bool,
+}
+impl Emitter {
+	
+	/// #### update
+	/// Applies the volume and pan heard by the listener, starting the sound over if it's looping.
+	pub fn update(&mut self, listener: &Listener, gain: f32) -> &mut Self {
+		let (volume, pan) = listener.spatialize(self.position, &self.attenuation);
+		self.sound
+			.volume(self.volume * volume * gain)
+			.pan(pan);
+		
+		if self.playing && !self.sound.playing() {
+			if self.looping { self.sound.play(); } else { self.playing = false; }
+		}
+		
+		self
+	}
+	
+}
+
+/// #### EmitterId
+/// Handle to an Emitter added to the AudioHandler.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EmitterId(pub(crate) usize);
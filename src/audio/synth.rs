@@ -0,0 +1,332 @@
+use crate::sound::*;
+use super::rng::Rng;
+
+
+/// Frames rendered each time the synthesizer's stream asks for more audio.
+pub const SYNTH_BUFFER_FRAMES: usize = 1024;
+
+/// #### Waveform
+/// Shape of a synth oscillator.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum Waveform {
+	#[default]
+	Square,
+	Triangle,
+	Saw,
+	/// Random values held for half a cycle, so higher pitches sound brighter.
+	Noise,
+	/// Single cycle of samples from -1.0 to 1.0.
+	Wavetable(Vec<f32>),
+}
+impl Waveform {
+	
+	/// #### sample
+	/// Returns the value of the oscillator at the phase, from 0.0 to 1.0 through the cycle.
+	fn sample(&self, phase: f32, noise: f32) -> f32 {
+		match self {
+			Waveform::Square	=> if phase < 0.5 { 1.0 } else { -1.0 },
+			Waveform::Triangle	=> 1.0 - 4.0 * (phase - 0.5).abs(),
+			Waveform::Saw		=> 2.0 * phase - 1.0,
+			Waveform::Noise		=> noise,
+			Waveform::Wavetable(table) => {
+				if table.is_empty() { return 0.0 }
+				
+				table[((phase * table.len() as f32) as usize).min(table.len() - 1)]
+			}
+		}
+	}
+	
+}
+
+/// #### Envelope
+/// ADSR envelope, with times in seconds and sustain as a level from 0.0 to 1.0.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Envelope {
+	pub attack: f32,
+	pub decay: f32,
+	pub sustain: f32,
+	pub release: f32,
+}
+impl Default for Envelope {
+	fn default() -> Self {
+		Self {
+			attack: 0.01,
+			decay: 0.1,
+			sustain: 0.7,
+			release: 0.1,
+		}
+	}
+}
+impl Envelope {
+	
+	/// #### level
+	/// Returns the level of a note the time after it started, released at the input time if it has been.
+	pub fn level(&self, time: f32, released: Option<f32>) -> f32 {
+		match released {
+			Some(released) if time >= released => {
+				if self.release <= 0.0 { return 0.0 }
+				
+				self.held_level(released) * (1.0 - (time - released) / self.release).max(0.0)
+			}
+			_ => self.held_level(time),
+		}
+	}
+	/// #### held_level
+	/// Returns the level of a note that hasn't been released.
+	fn held_level(&self, time: f32) -> f32 {
+		if time < self.attack { return time / self.attack }
+		
+		let time = time - self.attack;
+		if time < self.decay { return 1.0 - (1.0 - self.sustain) * time / self.decay }
+		
+		self.sustain
+	}
+	
+}
+
+/// #### Synth
+/// Instrument played by the Synthesizer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Synth {
+	pub waveform: Waveform,
+	pub envelope: Envelope,
+	pub volume: f32,
+	/// Frequency in hertz played at a pitch of 1.0.
+	pub frequency: f32,
+}
+impl Default for Synth {
+	fn default() -> Self {
+		Self {
+			waveform: Waveform::Square,
+			envelope: Envelope::default(),
+			volume: 0.5,
+			frequency: 261.63,
+		}
+	}
+}
+impl Synth {
+	
+	/// #### from_json
+	/// Builds a Synth from its JSON description.
+	/// 
+	/// "waveform" is "square", "triangle", "saw", "noise" or an array of samples for a wavetable.
+	/// "attack", "decay", "sustain", "release", "volume" and "frequency" are optional.
+	pub fn from_json(value: &serde_json::Value) -> Option<Self> {
+		let waveform = match &value["waveform"] {
+			serde_json::Value::Array(table) => Waveform::Wavetable(table.iter().map(|v| v.as_f64().map(|v| v as f32)).collect::<Option<_>>()?),
+			serde_json::Value::String(name) => match name.to_lowercase().as_str() {
+				"square"	=> Waveform::Square,
+				"triangle"	=> Waveform::Triangle,
+				"saw"		=> Waveform::Saw,
+				"noise"		=> Waveform::Noise,
+				_ => return None,
+			},
+			_ => return None,
+		};
+		let default = Self::default();
+		let field = |name: &str, default: f32| value[name].as_f64().map_or(default, |v| v as f32);
+		
+		Some(Self {
+			waveform,
+			envelope: Envelope {
+				attack:		field("attack", default.envelope.attack),
+				decay:		field("decay", default.envelope.decay),
+				sustain:	field("sustain", default.envelope.sustain),
+				release:	field("release", default.envelope.release),
+			},
+			volume: field("volume", default.volume),
+			frequency: field("frequency", default.frequency),
+		})
+	}
+	
+}
+
+/// #### SynthVoice
+/// Note being played by the Synthesizer.
+#[derive(Debug, Clone)]
+struct SynthVoice {
+	instrument: usize,
+	frequency: f32,
+	volume: f32,
+	pan: f32,
+	duration: f32,
+	
+	phase: f32,
+	time: f32,
+	released: Option<f32>,
+	noise: f32,
+}
+
+/// #### Synthesizer
+/// Generates the notes of synth instruments, streamed to Raylib or rendered offline.
+/// 
+/// Instruments line up with the instruments of a Midi track, None leaving that one to its sample.
+#[derive(Debug, Clone)]
+pub struct Synthesizer {
+	pub instruments: Vec<Option<Synth>>,
+	pub sample_rate: u32,
+	pub polyphony: usize,
+	pub volume: f32,
+	
+	voices: Vec<SynthVoice>,
+	stream: Option<AudioStream>,
+	rng: Rng,
+}
+impl Default for Synthesizer {
+	fn default() -> Self {
+		Self {
+			instruments: Vec::new(),
+			sample_rate: 44100,
+			polyphony: 16,
+			volume: 1.0,
+			voices: Vec::new(),
+			stream: None,
+			rng: Rng::new(0x1234_5678),
+		}
+	}
+}
+impl Synthesizer {
+	
+	/// #### new
+	/// Creates a Synthesizer playing the input instruments.
+	pub fn new(instruments: Vec<Option<Synth>>) -> Self {
+		Self { instruments, ..Default::default() }
+	}
+	/// #### plays
+	/// Checks whether the instrument is a synth.
+	pub fn plays(&self, instrument: usize) -> bool {
+		self.instruments.get(instrument).is_some_and(|synth| synth.is_some())
+	}
+	/// #### note_on
+	/// Starts a note on the instrument, released after the duration in seconds.
+	/// 
+	/// Pitch is relative to the instrument's frequency and pan goes from 0.0 left to 1.0 right.
+	/// When every voice is busy the oldest note is replaced.
+	pub fn note_on(&mut self, instrument: usize, pitch: f32, volume: f32, pan: f32, duration: f32) -> &mut Self {
+		let Some(Some(synth)) = self.instruments.get(instrument) else { return self };
+		
+		if self.voices.len() >= self.polyphony.max(1) {
+			let oldest = (0..self.voices.len()).max_by(|a, b| self.voices[*a].time.total_cmp(&self.voices[*b].time)).unwrap();
+			self.voices.remove(oldest);
+		}
+		self.voices.push(SynthVoice {
+			instrument,
+			frequency: synth.frequency * pitch,
+			volume,
+			pan,
+			duration,
+			phase: 0.0,
+			time: 0.0,
+			released: None,
+			noise: 0.0,
+		});
+		
+		self
+	}
+	/// #### active_voices
+	/// Returns the number of notes still sounding.
+	pub fn active_voices(&self) -> usize {
+		self.voices.len()
+	}
+	/// #### render
+	/// Generates the next frames as interleaved stereo samples from -1.0 to 1.0.
+	pub fn render(&mut self, frames: usize) -> Vec<f32> {
+		let mut samples = vec![0.0; frames * 2];
+		let step = 1.0 / self.sample_rate.max(1) as f32;
+		
+		for frame in samples.chunks_exact_mut(2) {
+			for voice in self.voices.iter_mut() {
+				let Some(Some(synth)) = self.instruments.get(voice.instrument) else { continue };
+				
+				let level = synth.envelope.level(voice.time, voice.released) * synth.volume * voice.volume * self.volume;
+				let value = synth.waveform.sample(voice.phase, voice.noise) * level;
+				frame[0] += value * (2.0 * (1.0 - voice.pan)).min(1.0);
+				frame[1] += value * (2.0 * voice.pan).min(1.0);
+				
+				//* Advance the oscillator, picking new noise every half cycle */
+				let previous = voice.phase;
+				voice.phase = (voice.phase + voice.frequency * step).fract();
+				if voice.phase < previous || (previous < 0.5 && voice.phase >= 0.5) {
+					voice.noise = self.rng.float() * 2.0 - 1.0;
+				}
+				
+				voice.time += step;
+				if voice.released.is_none() && voice.time >= voice.duration { voice.released = Some(voice.time) }
+			}
+			
+			//* Forget notes that finished releasing */
+			let instruments = &self.instruments;
+			self.voices.retain(|voice| match (voice.released, instruments.get(voice.instrument)) {
+				(Some(released), Some(Some(synth))) => voice.time - released < synth.envelope.release,
+				(None, Some(Some(_))) => true,
+				_ => false,
+			});
+			
+			frame[0] = frame[0].clamp(-1.0, 1.0);
+			frame[1] = frame[1].clamp(-1.0, 1.0);
+		}
+		
+		samples
+	}
+	/// #### render_wave
+	/// Renders the next frames into a stereo 32-bit Wave, for exporting or turning into a Sound.
+	pub fn render_wave(&mut self, frames: usize) -> Wave {
+		let samples = self.render(frames);
+		
+		Wave::from_samples(&samples, self.sample_rate, 2)
+	}
+	
+	//= Streaming
+	/// #### start
+	/// Starts streaming the synthesizer to Raylib.
+	pub fn start(&mut self) -> &mut Self {
+		if self.stream.is_some() { return self }
+		
+		//* Only the synth's stream uses its buffer size, later streams get Raylib's default */
+		AudioStream::set_buffer_size_default(SYNTH_BUFFER_FRAMES as i32);
+		let mut stream = AudioStream::load(self.sample_rate, 32, 2);
+		AudioStream::set_buffer_size_default(0);
+		stream.play();
+		self.stream = Some(stream);
+		
+		self
+	}
+	/// #### update
+	/// Fills the stream's buffers as Raylib uses them up. Does nothing if the stream hasn't been started.
+	pub fn update(&mut self) -> &mut Self {
+		let Some(mut stream) = self.stream else { return self };
+		
+		while stream.processed() {
+			let samples = self.render(SYNTH_BUFFER_FRAMES);
+			stream.update(&samples, SYNTH_BUFFER_FRAMES as i32);
+		}
+		
+		self
+	}
+	/// #### pause
+	/// Pauses the stream, holding every note where it is.
+	pub fn pause(&mut self) -> &mut Self {
+		if let Some(stream) = &mut self.stream { stream.pause(); }
+		
+		self
+	}
+	/// #### resume
+	/// Resumes a paused stream.
+	pub fn resume(&mut self) -> &mut Self {
+		if let Some(stream) = &mut self.stream { stream.resume(); }
+		
+		self
+	}
+	/// #### stop
+	/// Stops streaming, unloading the stream and silencing every note.
+	pub fn stop(&mut self) -> &mut Self {
+		if let Some(mut stream) = self.stream.take() {
+			stream.stop();
+			stream.unload();
+		}
+		self.voices.clear();
+		
+		self
+	}
+	
+}
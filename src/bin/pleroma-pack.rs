@@ -0,0 +1,166 @@
+
+
+use std::{fs, path::{Component, Path, PathBuf}, process::exit};
+
+use pleroma::files::{compression::*, manifest::*, *};
+
+
+const USAGE: &str = "Usage:
+	pleroma-pack pack <manifest.json>
+	pleroma-pack list <archive.blk|archive.mnf>
+	pleroma-pack extract <archive.blk|archive.mnf> <directory> [name]
+	pleroma-pack verify <archive.blk|archive.mnf>
+
+Manifest:
+	{
+		\"output\": \"assets.blk\",
+		\"files\": [
+			{ \"name\": \"player\", \"path\": \"player.png\", \"type\": \"Image\", \"compression\": \"ZLib\" }
+		]
+	}
+
+	Paths are relative to the manifest. \"compression\" is optional and defaults to None.
+	An .mnf output writes a manifest with a single .blk volume, or splits the data across volumes of \"volume_size\" bytes if set.
+	Types: Image, Animation, Font, StandardSound, Model, JSON, Raw
+	Compression: None, ZLib, Deflate, GZ";
+
+fn main() {
+	let args: Vec<String> = std::env::args().collect();
+	
+	let result = match args.get(1).map(|arg| arg.as_str()) {
+		Some("pack")	if args.len() == 3 => pack(&args[2]),
+		Some("list")	if args.len() == 3 => list(&args[2]),
+		Some("extract")	if args.len() == 4 || args.len() == 5 => extract(&args[2], &args[3], args.get(4)),
+		Some("verify")	if args.len() == 3 => verify(&args[2]),
+		_ => Err(USAGE.to_string()),
+	};
+	
+	if let Err(message) = result {
+		eprintln!("{message}");
+		exit(1);
+	}
+}
+
+/// #### pack
+/// Builds an archive from the files listed in the manifest.
+fn pack(manifest: &str) -> Result<(), String> {
+	let text = fs::read_to_string(manifest).map_err(|e| format!("Failed to read manifest \"{manifest}\": {e}"))?;
+	let value: serde_json::Value = serde_json::from_str(&text).map_err(|e| format!("Failed to parse manifest \"{manifest}\": {e}"))?;
+	let root = Path::new(manifest).parent().unwrap_or(Path::new(""));
+	
+	let output = value["output"].as_str().ok_or("Manifest is missing \"output\".")?;
+	let files = value["files"].as_array().ok_or("Manifest is missing \"files\".")?;
+	
+	let output_path = root.join(output);
+	let filename = output_path.to_str().ok_or(format!("Output path \"{}\" is not valid UTF-8.", output_path.display()))?;
+	let volume_size = value["volume_size"].as_u64();
+	if volume_size.is_some() && !output.ends_with(".mnf") { return Err("\"volume_size\" needs an .mnf output.".to_string()) }
+	
+	let mut bulk = Bulk::new();
+	bulk.set_filename(filename);
+	for entry in files {
+		let name = entry["name"].as_str().ok_or("Manifest entry is missing \"name\".")?;
+		let path = entry["path"].as_str().ok_or(format!("Entry \"{name}\" is missing \"path\"."))?;
+		let file_type = entry["type"].as_str()
+			.and_then(|t| t.parse::<FileType>().ok())
+			.ok_or(format!("Entry \"{name}\" has an invalid \"type\"."))?;
+		let compression = match entry["compression"].as_str() {
+			Some(c) => c.parse::<CompressionType>().map_err(|_| format!("Entry \"{name}\" has an invalid \"compression\"."))?,
+			None => CompressionType::None,
+		};
+		
+		let data = fs::read(root.join(path)).map_err(|e| format!("Failed to read \"{path}\": {e}"))?;
+		bulk.add_compressed(name, file_type, data, compression, CompressionLevel::Best);
+	}
+	
+	//* Manifests without a volume size keep all the data in one volume */
+	match output.ends_with(".mnf") {
+		true => bulk.save_split(filename, volume_size.map_or(usize::MAX, |size| size as usize)),
+		false => bulk.save(),
+	}.map_err(|e| e.to_string())?;
+	println!("Packed {} files into \"{}\".", bulk.table.len(), bulk.filename);
+	
+	Ok(())
+}
+
+/// #### list
+/// Prints every entry in the archive.
+fn list(archive: &str) -> Result<(), String> {
+	let bulk = open(archive)?;
+	
+	for ptr in sorted(bulk.as_ref()) { println!("{ptr}"); }
+	
+	Ok(())
+}
+
+/// #### extract
+/// Writes the decompressed entries of the archive into the directory.
+fn extract(archive: &str, directory: &str, name: Option<&String>) -> Result<(), String> {
+	let bulk = open(archive)?;
+	fs::create_dir_all(directory).map_err(|e| format!("Failed to create \"{directory}\": {e}"))?;
+	
+	for ptr in sorted(bulk.as_ref()) {
+		if name.is_some_and(|n| *n != ptr.name) { continue }
+		
+		let data = bulk.read(&ptr.name).map_err(|e| e.to_string())?;
+		let path = entry_path(directory, &ptr.name)?;
+		if let Some(parent) = path.parent() { fs::create_dir_all(parent).map_err(|e| format!("Failed to create \"{}\": {e}", parent.display()))?; }
+		fs::write(&path, data).map_err(|e| format!("Failed to write \"{}\": {e}", path.display()))?;
+		println!("Extracted \"{}\".", ptr.name);
+	}
+	
+	Ok(())
+}
+
+/// #### entry_path
+/// Returns where the entry is extracted to in the directory, refusing names that would leave it.
+fn entry_path(directory: &str, name: &str) -> Result<PathBuf, String> {
+	let relative = Path::new(name);
+	if name.is_empty() || !relative.components().all(|component| matches!(component, Component::Normal(_))) {
+		return Err(format!("Refusing to extract \"{name}\" outside of \"{directory}\"."))
+	}
+	
+	Ok(Path::new(directory).join(relative))
+}
+
+/// #### verify
+/// Checks the archive checksum and the CRC32 of every entry.
+fn verify(archive: &str) -> Result<(), String> {
+	let bulk = open(archive)?;
+	
+	let mut failed = 0;
+	let pointers = sorted(bulk.as_ref());
+	for ptr in pointers.iter() {
+		match bulk.read(&ptr.name) {
+			Ok(_) => println!("[OK] {ptr}"),
+			Err(e) => {
+				println!("[FAILED] {e}\n{ptr}");
+				failed += 1;
+			}
+		}
+	}
+	
+	if failed > 0 { return Err(format!("{failed} entries failed verification.")) }
+	println!("Verified {} entries.", pointers.len());
+	
+	Ok(())
+}
+
+/// #### open
+/// Loads a Bulk, or a Manifest if the file ends in .mnf.
+fn open(archive: &str) -> Result<Box<dyn Archive>, String> {
+	if archive.ends_with(".mnf") {
+		Ok(Box::new(Manifest::load(archive).map_err(|e| e.to_string())?))
+	} else {
+		Ok(Box::new(Bulk::load(archive).map_err(|e| e.to_string())?))
+	}
+}
+
+/// #### sorted
+/// Returns the entries of the archive in order of name.
+fn sorted(bulk: &dyn Archive) -> Vec<&Pointer> {
+	let mut pointers = bulk.pointers();
+	pointers.sort_by(|a, b| a.name.cmp(&b.name));
+	
+	pointers
+}
@@ -13,6 +13,9 @@ pub enum PlError {
 	EditingRenderSize,
 	RenderTextureDoesntExist,
 	KeybindCalledAxisInBool,
+	AssetFailedToLoad,
+	AssetReloaded,
+	AssetFailedToReload,
 }
 impl Into<LogLevel> for PlError {
 	fn into(self) -> LogLevel {
@@ -24,6 +27,9 @@ impl Into<LogLevel> for PlError {
 			PlError::EditingRenderSize => LogLevel::Error,
 			PlError::RenderTextureDoesntExist => LogLevel::Error,
 			PlError::KeybindCalledAxisInBool => todo!(),
+			PlError::AssetFailedToLoad => LogLevel::Error,
+			PlError::AssetReloaded => LogLevel::Info,
+			PlError::AssetFailedToReload => LogLevel::Error,
 		}
 	}
 }
@@ -37,6 +43,9 @@ impl ToString for PlError {
 			PlError::EditingRenderSize => "[ERROR] - Attempted to edit render resolution while drawing to screen.".to_string(),
 			PlError::RenderTextureDoesntExist => "[ERROR] - Attempted to start drawing without a RenderTexture.".to_string(),
 			PlError::KeybindCalledAxisInBool => "[INFO] - Attempted to use axis in button press.".to_string(),
+			PlError::AssetFailedToLoad => "[ERROR] - Failed to load asset:".to_string(),
+			PlError::AssetReloaded => "[INFO] - Reloaded asset:".to_string(),
+			PlError::AssetFailedToReload => "[ERROR] - Failed to reload asset, keeping the previous version:".to_string(),
 		}
 	}
 }
\ No newline at end of file
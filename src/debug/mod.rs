@@ -51,12 +51,18 @@ impl Pleroma {
 	/// - Log file (if enabled)
 	/// - Screen (if enabled)
 	pub fn log(&mut self, message: errors::PlError) {
+		self.log_detail(message, "");
+	}
+	/// ### log_detail
+	/// Prints a message to Pleroma's log system, followed by the detail, such as the name of the asset it's about.
+	pub fn log_detail(&mut self, message: errors::PlError, detail: &str) {
 		let level_value: u8 = self.get_log_level().into();
 		let message_level: LogLevel = message.into();
 		let message_level_value: u8 = message_level.into();
 		
 		let sys_time = Local::now();
-		let formatted_message = format!("{}{}", sys_time.format("[%Y-%m-%e] [%T] "), message.to_string());
+		let mut formatted_message = format!("{}{}", sys_time.format("[%Y-%m-%e] [%T] "), message.to_string());
+		if !detail.is_empty() { formatted_message = format!("{formatted_message} {detail}"); }
 		
 		if level_value <= message_level_value {
 			//* Print to console */
@@ -1,6 +1,6 @@
 
 
-use std::io::prelude::*;
+use std::{fmt::Display, io::prelude::*, str::FromStr};
 use flate2::write::{DeflateEncoder, GzEncoder, ZlibEncoder};
 use flate2::read::{DeflateDecoder, GzDecoder, ZlibDecoder};
 use flate2::Compression;
@@ -9,7 +9,9 @@ use flate2::Compression;
 /// Compression types
 ///
 /// Decides what to do with the raw data.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
 pub enum CompressionType {
+	#[default]
 	None,
 	ZLib,
 	Deflate,
@@ -19,7 +21,9 @@ pub enum CompressionType {
 /// Compression level
 /// 
 /// How long you're willing to wait.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
 pub enum CompressionLevel {
+	#[default]
 	Best,
 	Fast,
 	None,
@@ -36,6 +40,50 @@ impl Into<Compression> for CompressionLevel {
 	}
 }
 
+impl From<u8> for CompressionType {
+	fn from(value: u8) -> Self {
+		match value {
+			1 => CompressionType::ZLib,
+			2 => CompressionType::Deflate,
+			3 => CompressionType::GZ,
+			_ => CompressionType::None,
+		}
+	}
+}
+impl From<CompressionType> for u8 {
+	fn from(value: CompressionType) -> Self {
+		match value {
+			CompressionType::None		=> 0,
+			CompressionType::ZLib		=> 1,
+			CompressionType::Deflate	=> 2,
+			CompressionType::GZ			=> 3,
+		}
+	}
+}
+impl FromStr for CompressionType {
+	type Err = ();
+	
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"None"		=> Ok(CompressionType::None),
+			"ZLib"		=> Ok(CompressionType::ZLib),
+			"Deflate"	=> Ok(CompressionType::Deflate),
+			"GZ"		=> Ok(CompressionType::GZ),
+			_			=> Err(()),
+		}
+	}
+}
+impl Display for CompressionType {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			CompressionType::None		=> write!(f, "None"),
+			CompressionType::ZLib		=> write!(f, "ZLib"),
+			CompressionType::Deflate	=> write!(f, "Deflate"),
+			CompressionType::GZ			=> write!(f, "GZ"),
+		}
+	}
+}
+
 impl CompressionType {
 
 	/// Decompress the data
@@ -65,6 +113,25 @@ impl CompressionType {
 			Self::None => { data }
 		}
 	}
+	/// Decompress the data, expecting exactly raw_size bytes
+	/// 
+	/// Returns None if the data is corrupt or decompresses to another size, without reading more than one byte past raw_size.
+	pub fn decompress_exact(&self, data: Vec<u8>, raw_size: usize) -> Option<Vec<u8>> {
+		let limit = raw_size as u64 + 1;
+		let mut result = Vec::new();
+		let read = match self {
+			Self::ZLib		=> ZlibDecoder::new(data.as_slice()).take(limit).read_to_end(&mut result),
+			Self::GZ		=> GzDecoder::new(data.as_slice()).take(limit).read_to_end(&mut result),
+			Self::Deflate	=> DeflateDecoder::new(data.as_slice()).take(limit).read_to_end(&mut result),
+			Self::None		=> {
+				result = data;
+				Ok(result.len())
+			}
+		};
+		if read.is_err() || result.len() != raw_size { return None }
+
+		Some(result)
+	}
 	/// Compress the data
 	pub fn compress(&self, data: Vec<u8>, compression: CompressionLevel) -> Vec<u8> {
 		match self {
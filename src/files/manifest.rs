@@ -0,0 +1,201 @@
+use std::{borrow::Cow, collections::{hash_map, HashMap}, fs::File, io::{Read, Seek, SeekFrom, Write}, path::Path, sync::Mutex};
+
+use super::*;
+
+
+/// Length of a single entry in the manifest table.
+pub const ENTRY_SIZE: usize = POINTER_SIZE + 4;
+
+/// #### Entry
+/// Pointer to data inside one of the volumes of a Manifest.
+#[derive(Debug, Default, Clone)]
+pub struct Entry {
+	pub volume: usize,
+	pub pointer: Pointer,
+}
+
+/// #### Manifest
+/// Index of entries spread across one or more .blk volumes.
+/// 
+/// Only the table is read when loading, entries are read from their volume when requested.
+#[derive(Debug)]
+pub struct Manifest {
+	pub filename: String,
+	pub checksum: u64,
+	pub volumes: Vec<String>,
+	pub table: HashMap<String, Entry>,
+	
+	files: Mutex<HashMap<usize, File>>,
+}
+impl Manifest {
+	
+	/// #### load
+	/// Loads the table of a Manifest from file.
+	/// 
+	/// The file starts with a checksum of the rest of the file.
+	/// Then the number of volumes followed by their filenames, each prefixed by its length.
+	/// Then comes the number of entries followed by the table, each entry being the volume number and a Pointer.
+	/// Volume filenames are relative to the manifest and pointer positions are relative to the start of the volume.
+	pub fn load(filename: &str) -> Result<Self, BulkError> {
+		let file = File::open(filename);
+		if file.is_err() { return Err(BulkError::FailedToOpen) }
+		
+		let mut buf = Vec::new();
+		if file.unwrap().read_to_end(&mut buf).is_err() { return Err(BulkError::FailedToRead) }
+		
+		//* Checksum */
+		let mut offset = 0;
+		let stored_checksum = read_u32(&buf, &mut offset)? as u64 + ((read_u32(&buf, &mut offset)? as u64) << 32);
+		if checksum(&buf[8..]) != stored_checksum { return Err(BulkError::ChecksumMismatch) }
+		
+		//* Volumes */
+		let volume_count = read_u32(&buf, &mut offset)? as usize;
+		let mut volumes = Vec::new();
+		for _ in 0..volume_count {
+			let length = *buf.get(offset).ok_or(BulkError::TruncatedTable)? as usize;
+			let name = buf.get(offset + 1..offset + 1 + length).ok_or(BulkError::TruncatedTable)?;
+			volumes.push(String::from_utf8_lossy(name).to_string());
+			offset += 1 + length;
+		}
+		
+		//* Table */
+		let entry_count = read_u32(&buf, &mut offset)? as usize;
+		if buf.len() < offset + (entry_count * ENTRY_SIZE) { return Err(BulkError::TruncatedTable) }
+		let mut table = HashMap::new();
+		for _ in 0..entry_count {
+			let volume = read_u32(&buf, &mut offset)? as usize;
+			if volume >= volumes.len() { return Err(BulkError::Malformed) }
+			
+			let pointer = Pointer::from_bytes(&buf[offset..offset + POINTER_SIZE]).ok_or(BulkError::TruncatedTable)?;
+			offset += POINTER_SIZE;
+			table.insert(pointer.name.clone(), Entry { volume, pointer });
+		}
+		
+		Ok(Self {
+			filename: filename.to_string(),
+			checksum: stored_checksum,
+			volumes,
+			table,
+			files: Mutex::new(HashMap::new()),
+		})
+	}
+	
+	/// #### volume_path
+	/// Returns the path of the volume relative to the working directory.
+	pub fn volume_path(&self, volume: usize) -> String {
+		let root = Path::new(&self.filename).parent().unwrap_or(Path::new(""));
+		
+		root.join(&self.volumes[volume]).to_string_lossy().to_string()
+	}
+	/// #### get_raw
+	/// Reads the data of the entry as it is stored in its volume.
+	/// 
+	/// Volumes are opened the first time they're read from and kept open afterwards.
+	pub fn get_raw(&self, name: &str) -> Result<Vec<u8>, BulkError> {
+		let entry = self.table.get(name).ok_or(BulkError::EntryNotFound(name.to_string()))?;
+		
+		let mut files = self.files.lock().map_err(|_| BulkError::FailedToRead)?;
+		let file = match files.entry(entry.volume) {
+			hash_map::Entry::Occupied(file) => file.into_mut(),
+			hash_map::Entry::Vacant(vacant) => vacant.insert(File::open(self.volume_path(entry.volume)).map_err(|_| BulkError::FailedToOpen)?),
+		};
+		
+		let mut data = vec![0; entry.pointer.size];
+		if file.seek(SeekFrom::Start(entry.pointer.position as u64)).is_err() { return Err(BulkError::FailedToRead) }
+		if file.read_exact(&mut data).is_err() { return Err(BulkError::EntryOutOfRange(name.to_string())) }
+		
+		Ok(data)
+	}
+	
+}
+impl Archive for Manifest {
+	fn pointers(&self) -> Vec<&Pointer> {
+		self.table.values().map(|entry| &entry.pointer).collect()
+	}
+	fn pointer(&self, name: &str) -> Option<&Pointer> {
+		Some(&self.table.get(name)?.pointer)
+	}
+	fn read(&self, name: &str) -> Result<Cow<'_, [u8]>, BulkError> {
+		let ptr = self.pointer(name).ok_or(BulkError::EntryNotFound(name.to_string()))?;
+		let data = self.get_raw(name)?;
+		ptr.check(&data)?;
+		
+		ptr.decompress(data).map(Cow::Owned)
+	}
+}
+
+impl Bulk {
+	
+	/// #### save_split
+	/// Saves the Bulk as a manifest and volumes of data.
+	/// 
+	/// Volumes are named after the manifest and hold at most `volume_size` bytes, unless a single entry is larger.
+	pub fn save_split(&mut self, filename: &str, volume_size: usize) -> Result<(), BulkError> {
+		let mut names: Vec<String> = self.table.keys().cloned().collect();
+		names.sort();
+		
+		let path = Path::new(filename);
+		let stem = path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+		let root = path.parent().unwrap_or(Path::new(""));
+		
+		//* Split data into volumes */
+		let mut volumes: Vec<Vec<u8>> = vec![Vec::new()];
+		let mut entries = Vec::new();
+		for name in names.iter() {
+			if name.len() > 16 { return Err(BulkError::NameTooLong) }
+			
+			let bytes = self.get_raw(name).ok_or(BulkError::EntryOutOfRange(name.clone()))?;
+			let current = volumes.last().unwrap();
+			if !current.is_empty() && current.len() + bytes.len() > volume_size { volumes.push(Vec::new()) }
+			
+			let volume = volumes.len() - 1;
+			let mut pointer = self.table[name].clone();
+			pointer.position = volumes[volume].len();
+			volumes[volume].extend_from_slice(bytes);
+			entries.push(Entry { volume, pointer });
+		}
+		
+		//* Write volumes, whose names are stored with a single byte for their length */
+		if format!("{stem}{}.blk", volumes.len() - 1).len() > 255 { return Err(BulkError::VolumeNameTooLong) }
+		let mut buf = Vec::new();
+		buf.extend_from_slice(&to_u8(volumes.len() as u32));
+		for (i, data) in volumes.iter().enumerate() {
+			let volume_name = format!("{stem}{i}.blk");
+			let file = File::create(root.join(&volume_name));
+			if file.is_err() { return Err(BulkError::FailedToOpen) }
+			if file.unwrap().write_all(data).is_err() { return Err(BulkError::FailedToWrite) }
+			
+			buf.push(volume_name.len() as u8);
+			buf.extend_from_slice(volume_name.as_bytes());
+		}
+		
+		//* Write manifest */
+		buf.extend_from_slice(&to_u8(entries.len() as u32));
+		for entry in entries.iter() {
+			buf.extend_from_slice(&to_u8(entry.volume as u32));
+			buf.extend_from_slice(&entry.pointer.to_bytes()?);
+		}
+		
+		let checksum = checksum(&buf);
+		let mut contents = Vec::with_capacity(8 + buf.len());
+		contents.extend_from_slice(&to_u8(checksum as u32));
+		contents.extend_from_slice(&to_u8((checksum >> 32) as u32));
+		contents.extend_from_slice(&buf);
+		
+		let file = File::create(filename);
+		if file.is_err() { return Err(BulkError::FailedToOpen) }
+		if file.unwrap().write_all(&contents).is_err() { return Err(BulkError::FailedToWrite) }
+		
+		Ok(())
+	}
+	
+}
+
+/// #### read_u32
+/// Reads a u32 from the buffer and advances the offset.
+fn read_u32(buf: &[u8], offset: &mut usize) -> Result<u32, BulkError> {
+	let bytes = buf.get(*offset..*offset + 4).ok_or(BulkError::Malformed)?;
+	*offset += 4;
+	
+	Ok(from_u8([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
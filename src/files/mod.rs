@@ -1,9 +1,16 @@
 
 
-use std::{collections::HashMap, fmt::Display, fs::File, io::Read};
+use std::{borrow::Cow, collections::HashMap, fmt::Display, fs::File, io::{Read, Write}, str::FromStr};
 
+use crate::{font::*, image::*, model::*, sound::*};
+use self::compression::*;
 
-#[derive(Debug, Default, Clone)]
+pub mod compression;
+pub mod manifest;
+pub mod vfs;
+
+
+#[derive(Debug, Default, Clone, PartialEq)]
 pub enum FileType {
 	Image,
 	Animation,
@@ -12,6 +19,8 @@ pub enum FileType {
 	Model,
 	#[default]
 	JSON,
+	/// Data of a type Pleroma doesn't know.
+	Raw,
 }
 impl From<u8> for FileType {
 	fn from(value: u8) -> Self {
@@ -21,10 +30,56 @@ impl From<u8> for FileType {
 			3 => FileType::Font,
 			4 => FileType::StandardSound,
 			5 => FileType::Model,
+			6 => FileType::Raw,
 			_ => FileType::JSON,
 		}
 	}
 }
+impl FileType {
+	
+	/// #### from_extension
+	/// Guesses the type of a file from its extension, or Raw if it isn't known.
+	pub fn from_extension(extension: &str) -> Self {
+		match extension.to_lowercase().as_str() {
+			"png" | "jpg" | "jpeg" | "bmp" | "gif" | "qoi" | "tga"	=> FileType::Image,
+			"ttf" | "otf" | "fnt"									=> FileType::Font,
+			"wav" | "ogg" | "mp3" | "flac" | "qoa"					=> FileType::StandardSound,
+			"glb" | "gltf" | "obj" | "iqm" | "vox" | "m3d"			=> FileType::Model,
+			"json"													=> FileType::JSON,
+			_														=> FileType::Raw,
+		}
+	}
+	
+}
+impl From<FileType> for u8 {
+	fn from(value: FileType) -> Self {
+		match value {
+			FileType::Image			=> 1,
+			FileType::Animation		=> 2,
+			FileType::Font			=> 3,
+			FileType::StandardSound	=> 4,
+			FileType::Model			=> 5,
+			FileType::JSON			=> 0,
+			FileType::Raw			=> 6,
+		}
+	}
+}
+impl FromStr for FileType {
+	type Err = ();
+	
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"Image"			=> Ok(FileType::Image),
+			"Animation"		=> Ok(FileType::Animation),
+			"Font"			=> Ok(FileType::Font),
+			"StandardSound"	=> Ok(FileType::StandardSound),
+			"Model"			=> Ok(FileType::Model),
+			"JSON"			=> Ok(FileType::JSON),
+			"Raw"			=> Ok(FileType::Raw),
+			_				=> Err(()),
+		}
+	}
+}
 impl Display for FileType {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 		match self {
@@ -34,35 +89,129 @@ impl Display for FileType {
 			FileType::StandardSound	=> write!(f, "StandardSound"),
 			FileType::Model			=> write!(f, "Model"),
 			FileType::JSON			=> write!(f, "JSON"),
+			FileType::Raw			=> write!(f, "Raw"),
 		}
 	}
 }
 
-#[derive(Debug, Default, Clone)]
-pub enum FileCompression {
-	#[default]
-	None,
-	Base64,
-}
-
+/// #### Pointer
+/// Entry in the Bulk table.
+/// 
+/// `size` is the number of bytes stored in the Bulk, `raw_size` is the size after decompression.
+/// `crc` is the CRC32 of the stored bytes.
 #[derive(Debug, Default, Clone)]
 pub struct Pointer {
 	pub name: String,
 	pub position: usize,
 	pub size: usize,
+	pub raw_size: usize,
 	pub file_type: FileType,
+	pub compression: CompressionType,
+	pub crc: u32,
+}
+impl Pointer {
+	
+	/// #### from_bytes
+	/// Reads a Pointer from a table entry, or returns None if the entry is shorter than POINTER_SIZE.
+	pub fn from_bytes(buf: &[u8]) -> Option<Self> {
+		if buf.len() < POINTER_SIZE { return None }
+		
+		let mut ptr = Pointer::default();
+		let length = buf[..16].iter().position(|byte| *byte == 0).unwrap_or(16);
+		ptr.name = String::from_utf8_lossy(&buf[..length]).into_owned();
+		ptr.position	= from_u8([buf[16], buf[17], buf[18], buf[19]]) as usize;
+		ptr.size		= from_u8([buf[20], buf[21], buf[22], buf[23]]) as usize;
+		ptr.file_type	= FileType::from(buf[24]);
+		ptr.compression	= CompressionType::from(buf[25]);
+		ptr.raw_size	= from_u8([buf[26], buf[27], buf[28], buf[29]]) as usize;
+		ptr.crc			= from_u8([buf[30], buf[31], buf[32], buf[33]]);
+		
+		Some(ptr)
+	}
+	/// #### to_bytes
+	/// Converts the Pointer into a table entry. Names can be at most 16 bytes.
+	pub fn to_bytes(&self) -> Result<[u8;POINTER_SIZE], BulkError> {
+		if self.name.len() > 16 { return Err(BulkError::NameTooLong) }
+		
+		let mut buf = [0u8;POINTER_SIZE];
+		buf[..self.name.len()].copy_from_slice(self.name.as_bytes());
+		buf[16..20].copy_from_slice(&to_u8(self.position as u32));
+		buf[20..24].copy_from_slice(&to_u8(self.size as u32));
+		buf[24] = self.file_type.clone().into();
+		buf[25] = self.compression.into();
+		buf[26..30].copy_from_slice(&to_u8(self.raw_size as u32));
+		buf[30..34].copy_from_slice(&to_u8(self.crc));
+		
+		Ok(buf)
+	}
+	/// #### check
+	/// Checks the stored data of the entry against its CRC32.
+	pub fn check(&self, data: &[u8]) -> Result<(), BulkError> {
+		if data.len() != self.size { return Err(BulkError::EntryOutOfRange(self.name.clone())) }
+		if crc32(data) != self.crc { return Err(BulkError::CrcMismatch(self.name.clone())) }
+		
+		Ok(())
+	}
+	/// #### decompress
+	/// Decompresses the stored data of the entry, checking it against the raw size.
+	pub fn decompress(&self, data: Vec<u8>) -> Result<Vec<u8>, BulkError> {
+		self.compression.decompress_exact(data, self.raw_size).ok_or(BulkError::FailedToDecompress(self.name.clone()))
+	}
+	
 }
 impl Display for Pointer {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-		write!(f, "Bulk File \"{}\"\n\tType:\t\t{} \n\tPosition:\t{:#08X},\n\tSize:\t\t{},",
+		write!(f, "Bulk File \"{}\"\n\tType:\t\t{} \n\tPosition:\t{:#08X},\n\tSize:\t\t{},\n\tCompression:\t{},\n\tRaw Size:\t{},\n\tCRC32:\t\t{:#010X},",
 			self.name,
 			self.file_type,
 			self.position,
 			self.size,
+			self.compression,
+			self.raw_size,
+			self.crc,
 		)
 	}
 }
 
+/// #### BulkError
+/// Errors that can occur while reading or writing a Bulk.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BulkError {
+	FailedToOpen,
+	FailedToRead,
+	FailedToWrite,
+	Malformed,
+	NameTooLong,
+	VolumeNameTooLong,
+	TruncatedTable,
+	ChecksumMismatch,
+	EntryNotFound(String),
+	EntryOutOfRange(String),
+	CrcMismatch(String),
+	FailedToDecompress(String),
+}
+impl Display for BulkError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			BulkError::FailedToOpen	=> write!(f, "Failed to open Bulk file."),
+			BulkError::FailedToRead	=> write!(f, "Failed to read Bulk file."),
+			BulkError::FailedToWrite	=> write!(f, "Failed to write Bulk file."),
+			BulkError::Malformed	=> write!(f, "Bulk file is malformed."),
+			BulkError::NameTooLong	=> write!(f, "Bulk entry names can be at most 16 bytes."),
+			BulkError::VolumeNameTooLong	=> write!(f, "Manifest volume names can be at most 255 bytes."),
+			BulkError::TruncatedTable	=> write!(f, "Bulk file ends before the end of its table."),
+			BulkError::ChecksumMismatch	=> write!(f, "Bulk file doesn't match its checksum."),
+			BulkError::EntryNotFound(name)		=> write!(f, "Bulk entry \"{name}\" doesn't exist."),
+			BulkError::EntryOutOfRange(name)	=> write!(f, "Bulk entry \"{name}\" points outside of the file."),
+			BulkError::CrcMismatch(name)		=> write!(f, "Bulk entry \"{name}\" doesn't match its CRC32."),
+			BulkError::FailedToDecompress(name)	=> write!(f, "Failed to decompress Bulk entry \"{name}\"."),
+		}
+	}
+}
+
+/// Length of a single entry in the pointer table.
+pub const POINTER_SIZE: usize = 34;
+
 #[derive(Debug)]
 pub struct Bulk {
 	pub filename: String,
@@ -74,51 +223,40 @@ impl Bulk {
 	
 	/// #### load
 	/// Loads Bulk from file.
-	pub fn load(filename: &str) -> Result<Self, ()> {
+	/// 
+	/// The file starts with a checksum of the rest of the file, then the number of entries, the pointer table and the data.
+	/// Each table entry holds the name (16 bytes), position, size, file type, compression type, raw size and CRC32.
+	/// Pointer positions are relative to the start of the data.
+	pub fn load(filename: &str) -> Result<Self, BulkError> {
 		let file = File::open(filename);
-		if file.is_err() {
-			//* ERROR
-			return Err(());
-		}
+		if file.is_err() { return Err(BulkError::FailedToOpen) }
 		
 		let mut buf = Vec::new();
 		let result = file.unwrap().read_to_end(&mut buf);
-		if result.is_err() {
-			//* ERROR
-			return Err(());
-		}
+		if result.is_err() { return Err(BulkError::FailedToRead) }
+		
+		if buf.len() < 12 { return Err(BulkError::TruncatedTable) }
+		let stored_checksum = from_u8([buf[0], buf[1], buf[2], buf[3]]) as u64 + ((from_u8([buf[4], buf[5], buf[6], buf[7]]) as u64) << 32);
+		let table_mem_count = from_u8([buf[8], buf[9], buf[10], buf[11]]) as usize;
+		let data_start = 12 + (table_mem_count * POINTER_SIZE);
+		if buf.len() < data_start { return Err(BulkError::TruncatedTable) }
 		
-		let table_mem_count = buf[0] as u32 + ((buf[1] as u32) << 8) + ((buf[2] as u32) << 16) + ((buf[3] as u32) << 24);
-		let table_length = table_mem_count * 25;
+		let mut table = HashMap::new();
 		for i in 0..table_mem_count {
-			let mut ptr = Pointer::default();
-			for v in 0..16 {
-				let val = buf[(4 + (i * 25) + v) as usize] as char;
-				if val != '\0' { ptr.name.push(val) }
-			}
-			ptr.position += ((buf[(4 + (i * 25) + 16) as usize]) as usize) <<  0;
-			ptr.position += ((buf[(4 + (i * 25) + 17) as usize]) as usize) <<  8;
-			ptr.position += ((buf[(4 + (i * 25) + 18) as usize]) as usize) << 16;
-			ptr.position += ((buf[(4 + (i * 25) + 19) as usize]) as usize) << 24;
-			ptr.size	 += ((buf[(4 + (i * 25) + 20) as usize]) as usize) <<  0;
-			ptr.size	 += ((buf[(4 + (i * 25) + 21) as usize]) as usize) <<  8;
-			ptr.size	 += ((buf[(4 + (i * 25) + 22) as usize]) as usize) << 16;
-			ptr.size	 += ((buf[(4 + (i * 25) + 23) as usize]) as usize) << 24;
-			ptr.file_type = FileType::from(buf[(4 + (i * 24) + 24) as usize]);
+			let offset = 12 + (i * POINTER_SIZE);
+			let ptr = Pointer::from_bytes(&buf[offset..offset + POINTER_SIZE]).ok_or(BulkError::TruncatedTable)?;
 			
-			println!("{ptr}");
-		}
-		
-		let mut data = Vec::new();
-		for i in buf.bytes() {
-			if i.is_ok()  { data.push(i.unwrap()); }
+			if data_start + ptr.position + ptr.size > buf.len() { return Err(BulkError::EntryOutOfRange(ptr.name)) }
+			
+			table.insert(ptr.name.clone(), ptr);
 		}
+		if checksum(&buf[8..]) != stored_checksum { return Err(BulkError::ChecksumMismatch) }
 		
 		Ok(Self {
 			filename: filename.to_string(),
-			checksum: 0,
-			table: HashMap::new(),
-			data,
+			checksum: stored_checksum,
+			table,
+			data: buf.split_off(data_start),
 		})
 	}
 	/// #### new
@@ -133,18 +271,271 @@ impl Bulk {
 	}
 	/// #### save
 	/// Saves bulk to file.
-	pub fn save(&mut self) {}
-	///// #### set_filename
-	///// Sets the filename to be saved as.
-	//pub fn set_filename(&mut self, filename: &str) -> &mut Self {}
-	///// #### calc_checksum
-	///// Calculates the checksum for the data.
-	//pub fn calc_checksum(&mut self) -> &mut Self {}
-	//
-	pub fn get(&mut self, name: &str) -> 
+	/// 
+	/// Entries are written in order of name, so the same contents always produce the same file.
+	pub fn save(&mut self) -> Result<(), BulkError> {
+		let body = self.compact()?;
+		self.checksum = checksum(&body);
+		
+		let mut buf = Vec::with_capacity(8 + body.len());
+		buf.extend_from_slice(&to_u8(self.checksum as u32));
+		buf.extend_from_slice(&to_u8((self.checksum >> 32) as u32));
+		buf.extend_from_slice(&body);
+		
+		let file = File::create(&self.filename);
+		if file.is_err() { return Err(BulkError::FailedToOpen) }
+		if file.unwrap().write_all(&buf).is_err() { return Err(BulkError::FailedToWrite) }
+		
+		Ok(())
+	}
+	/// #### compact
+	/// Removes unused data, sorts the entries by name and returns the file contents that follow the checksum.
+	fn compact(&mut self) -> Result<Vec<u8>, BulkError> {
+		let mut names: Vec<String> = self.table.keys().cloned().collect();
+		names.sort();
+		
+		//* Compact data */
+		let mut table = HashMap::new();
+		let mut data = Vec::new();
+		for name in names.iter() {
+			if name.len() > 16 { return Err(BulkError::NameTooLong) }
+			
+			let mut ptr = self.table[name].clone();
+			let bytes = self.get_raw(name).ok_or(BulkError::EntryOutOfRange(name.clone()))?;
+			ptr.position = data.len();
+			data.extend_from_slice(bytes);
+			table.insert(name.clone(), ptr);
+		}
+		self.table = table;
+		self.data = data;
+		
+		//* Write table */
+		let mut buf = Vec::with_capacity(4 + (names.len() * POINTER_SIZE) + self.data.len());
+		buf.extend_from_slice(&to_u8(names.len() as u32));
+		for name in names.iter() { buf.extend_from_slice(&self.table[name].to_bytes()?); }
+		buf.extend_from_slice(&self.data);
+		
+		Ok(buf)
+	}
+	/// #### set_filename
+	/// Sets the filename to be saved as.
+	pub fn set_filename(&mut self, filename: &str) -> &mut Self {
+		self.filename = filename.to_string();
+		
+		self
+	}
+	/// #### calc_checksum
+	/// Calculates the checksum for the table and data, as it would be saved.
+	pub fn calc_checksum(&mut self) -> Result<&mut Self, BulkError> {
+		let body = self.compact()?;
+		self.checksum = checksum(&body);
+		
+		Ok(self)
+	}
+	/// #### add
+	/// Adds an uncompressed entry to the Bulk, replacing any existing entry of the same name.
+	pub fn add(&mut self, name: &str, file_type: FileType, data: Vec<u8>) -> &mut Self {
+		self.add_compressed(name, file_type, data, CompressionType::None, CompressionLevel::None)
+	}
+	/// #### add_compressed
+	/// Compresses the data and adds it to the Bulk, replacing any existing entry of the same name.
+	pub fn add_compressed(&mut self, name: &str, file_type: FileType, data: Vec<u8>, compression: CompressionType, level: CompressionLevel) -> &mut Self {
+		let raw_size = data.len();
+		let data = compression.compress(data, level);
+		let ptr = Pointer {
+			name: name.to_string(),
+			position: self.data.len(),
+			size: data.len(),
+			raw_size,
+			file_type,
+			compression,
+			crc: crc32(&data),
+		};
+		self.data.extend(data);
+		self.table.insert(name.to_string(), ptr);
+		
+		self
+	}
+	/// #### add_file
+	/// Reads a file from disk and adds it to the Bulk.
+	pub fn add_file(&mut self, name: &str, file_type: FileType, filename: &str) -> Result<&mut Self, BulkError> {
+		let file = File::open(filename);
+		if file.is_err() { return Err(BulkError::FailedToOpen) }
+		
+		let mut buf = Vec::new();
+		if file.unwrap().read_to_end(&mut buf).is_err() { return Err(BulkError::FailedToRead) }
+		
+		Ok(self.add(name, file_type, buf))
+	}
 	
+	/// #### get_raw
+	/// Returns the data of the entry as it is stored in the Bulk.
+	pub fn get_raw(&self, name: &str) -> Option<&[u8]> {
+		let ptr = self.table.get(name)?;
+		
+		self.data.get(ptr.position..ptr.position + ptr.size)
+	}
+	
+}
+impl Archive for Bulk {
+	fn pointers(&self) -> Vec<&Pointer> {
+		self.table.values().collect()
+	}
+	fn pointer(&self, name: &str) -> Option<&Pointer> {
+		self.table.get(name)
+	}
+	fn read(&self, name: &str) -> Result<Cow<'_, [u8]>, BulkError> {
+		let ptr = self.table.get(name).ok_or(BulkError::EntryNotFound(name.to_string()))?;
+		let data = self.get_raw(name).ok_or(BulkError::EntryOutOfRange(name.to_string()))?;
+		ptr.check(data)?;
+		if ptr.compression == CompressionType::None { return Ok(Cow::Borrowed(data)) }
+		
+		ptr.decompress(data.to_vec()).map(Cow::Owned)
+	}
+}
+
+/// #### Archive
+/// Shared interface for reading named entries out of Bulk-style archives.
+pub trait Archive: std::fmt::Debug {
+	
+	/// #### pointers
+	/// Returns every entry in the table.
+	fn pointers(&self) -> Vec<&Pointer>;
+	/// #### pointer
+	/// Returns the table entry with the input name.
+	fn pointer(&self, name: &str) -> Option<&Pointer>;
+	/// #### read
+	/// Returns the decompressed data of the entry, checking it against its CRC32.
+	fn read(&self, name: &str) -> Result<Cow<'_, [u8]>, BulkError>;
+	
+	/// #### get
+	/// Returns the decompressed data of the entry if it exists and is intact.
+	fn get(&self, name: &str) -> Option<Cow<'_, [u8]>> {
+		self.read(name).ok()
+	}
+	/// #### verify
+	/// Reads every entry, returning the first error found.
+	fn verify(&self) -> Result<(), BulkError> {
+		for ptr in self.pointers() { self.read(&ptr.name)?; }
+		
+		Ok(())
+	}
+
+	/// #### get_typed
+	/// Returns the decompressed data of the entry if it exists and is of the input type.
+	fn get_typed(&self, name: &str, file_type: FileType) -> Option<Cow<'_, [u8]>> {
+		if self.pointer(name)?.file_type != file_type { return None }
+		
+		self.get(name)
+	}
+	/// #### get_image
+	/// Loads the entry as an Image.
+	fn get_image(&self, name: &str) -> Option<Image> {
+		let data = self.get_typed(name, FileType::Image)?;
+		let image = Image::load_from_memory(extension(&data)?, &mut data.to_vec());
+		
+		if image.is_ready() { Some(image) } else { None }
+	}
+	/// #### get_font
+	/// Loads the entry as a Font of the input size.
+	fn get_font(&self, name: &str, font_size: i32) -> Option<Font> {
+		let data = self.get_typed(name, FileType::Font)?;
+		let font = Font::load_from_memory(extension(&data)?, data.to_vec(), font_size, Vec::new());
+		
+		if font.ready() { Some(font) } else { None }
+	}
+	/// #### get_sound
+	/// Loads the entry as a Sound.
+	fn get_sound(&self, name: &str) -> Option<Sound> {
+		let data = self.get_typed(name, FileType::StandardSound)?;
+		
+		Sound::load_from_memory(extension(&data)?, &data)
+	}
+	/// #### get_music
+	/// Loads the entry as a Music stream.
+	fn get_music(&self, name: &str) -> Option<Music> {
+		let data = self.get_typed(name, FileType::StandardSound)?;
+		
+		Music::load_from_memory(extension(&data)?, data.into_owned())
+	}
+	/// #### get_model
+	/// Loads the entry as a Model.
+	fn get_model(&self, name: &str) -> Option<ModelRl> {
+		let data = self.get_typed(name, FileType::Model)?;
+		let mut model = ModelRl::load_from_memory(extension(&data)?, &data);
+		
+		if model.ready() { Some(model) } else { None }
+	}
+	/// #### get_json
+	/// Parses the entry as JSON.
+	fn get_json(&self, name: &str) -> Option<serde_json::Value> {
+		let data = self.get_typed(name, FileType::JSON)?;
+		
+		serde_json::from_slice(&data).ok()
+	}
+	
+}
+
+/// #### extension
+/// Guesses the file extension of the data from its header, for use with Raylib's memory loaders.
+/// 
+/// Returns None for data it doesn't recognize.
+pub fn extension(data: &[u8]) -> Option<&'static str> {
+	let extension = match data {
+		[0x89, b'P', b'N', b'G', ..]	=> ".png",
+		[0xFF, 0xD8, ..]				=> ".jpg",
+		[b'B', b'M', ..]				=> ".bmp",
+		[b'G', b'I', b'F', ..]			=> ".gif",
+		[b'q', b'o', b'i', b'f', ..]	=> ".qoi",
+		[b'O', b'T', b'T', b'O', ..]	=> ".otf",
+		[b'R', b'I', b'F', b'F', ..]	=> ".wav",
+		[b'O', b'g', b'g', b'S', ..]	=> ".ogg",
+		[b'f', b'L', b'a', b'C', ..]	=> ".flac",
+		[b'I', b'D', b'3', ..]			=> ".mp3",
+		[b'q', b'o', b'a', b'f', ..]	=> ".qoa",
+		[b'g', b'l', b'T', b'F', ..]	=> ".glb",
+		[0x00, 0x01, 0x00, 0x00, ..]	=> ".ttf",
+		[b't', b'r', b'u', b'e', ..]	=> ".ttf",
+		[b'I', b'N', b'T', b'E', b'R', b'Q', b'U', b'A', b'K', b'E', b'M', b'O', b'D', b'E', b'L', ..] => ".iqm",
+		[b'3', b'D', b'M', b'O', ..] | [b'3', b'd', b'm', b'o', ..] => ".m3d",
+		[b'V', b'O', b'X', b' ', ..]	=> ".vox",
+		//* OBJ has no header, but is text with vertex lines */
+		_ if is_obj(data)				=> ".obj",
+		_								=> return None,
+	};
+	
+	Some(extension)
+}
+/// #### is_obj
+/// Checks whether the data looks like an OBJ model.
+fn is_obj(data: &[u8]) -> bool {
+	let Ok(text) = std::str::from_utf8(data) else { return false };
+	
+	text.lines().any(|line| line.trim_start().starts_with("v "))
 }
 
 pub fn from_u8(arr: [u8;4]) -> u32 {
 	arr[0] as u32 + ((arr[1] as u32) << 8) + ((arr[2] as u32) << 16) + ((arr[3] as u32) << 24)
-}
\ No newline at end of file
+}
+pub fn to_u8(value: u32) -> [u8;4] {
+	[value as u8, (value >> 8) as u8, (value >> 16) as u8, (value >> 24) as u8]
+}
+/// #### checksum
+/// 64-bit FNV-1a hash of the data.
+pub fn checksum(data: &[u8]) -> u64 {
+	let mut hash: u64 = 0xcbf29ce484222325;
+	for byte in data {
+		hash ^= *byte as u64;
+		hash = hash.wrapping_mul(0x100000001b3);
+	}
+	
+	hash
+}
+/// #### crc32
+/// CRC32 of the data.
+pub fn crc32(data: &[u8]) -> u32 {
+	let mut crc = flate2::Crc::new();
+	crc.update(data);
+	
+	crc.sum()
+}
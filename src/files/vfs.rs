@@ -0,0 +1,153 @@
+use std::{borrow::Cow, collections::HashMap, fs, path::{Path, PathBuf}};
+
+use super::*;
+
+
+/// #### Vfs
+/// Virtual filesystem that looks up entries across several archives and directories.
+/// 
+/// Archives are searched from the most recently mounted to the first, so patches and mods mounted after the base archive override its entries.
+/// Loose files in mounted directories override every archive.
+#[derive(Debug, Default)]
+pub struct Vfs {
+	archives: Vec<Box<dyn Archive + Send + Sync>>,
+	directories: Vec<PathBuf>,
+	loose: HashMap<String, (PathBuf, Pointer)>,
+	collisions: Vec<String>,
+}
+impl Vfs {
+	
+	/// #### new
+	/// Creates a Vfs with nothing mounted.
+	pub fn new() -> Self {
+		Self::default()
+	}
+	/// #### mount
+	/// Mounts an archive above everything mounted before it.
+	pub fn mount(&mut self, archive: impl Archive + Send + Sync + 'static) -> &mut Self {
+		self.archives.push(Box::new(archive));
+		
+		self
+	}
+	/// #### mount_bulk
+	/// Loads a Bulk and mounts it.
+	pub fn mount_bulk(&mut self, filename: &str) -> Result<&mut Self, BulkError> {
+		let bulk = Bulk::load(filename)?;
+		
+		Ok(self.mount(bulk))
+	}
+	/// #### mount_manifest
+	/// Loads a Manifest and mounts it.
+	pub fn mount_manifest(&mut self, filename: &str) -> Result<&mut Self, BulkError> {
+		let manifest = manifest::Manifest::load(filename)?;
+		
+		Ok(self.mount(manifest))
+	}
+	/// #### mount_directory
+	/// Mounts a directory of loose files above every archive.
+	/// 
+	/// Files are named by their path relative to the directory, without the extension. ("sprites/player.png" becomes "sprites/player")
+	/// Files that only differ by extension collide, and the first one in order of path is used. Check collisions for them.
+	pub fn mount_directory(&mut self, directory: &str) -> &mut Self {
+		self.directories.push(PathBuf::from(directory));
+		
+		self.rescan()
+	}
+	/// #### rescan
+	/// Rebuilds the list of loose files from the mounted directories.
+	pub fn rescan(&mut self) -> &mut Self {
+		self.loose.clear();
+		self.collisions.clear();
+		for directory in self.directories.clone().iter() {
+			let mut found = HashMap::new();
+			scan(directory, directory, &mut found, &mut self.collisions);
+			self.loose.extend(found);
+		}
+		
+		self
+	}
+	/// #### collisions
+	/// Returns the names of loose files that only differ by extension from another file in the same directory.
+	pub fn collisions(&self) -> &[String] {
+		&self.collisions
+	}
+	/// #### contains
+	/// Checks whether any mounted archive or directory has the entry.
+	pub fn contains(&self, name: &str) -> bool {
+		self.pointer(name).is_some()
+	}
+	/// #### names
+	/// Returns the names of every entry available, sorted.
+	pub fn names(&self) -> Vec<String> {
+		let mut names: Vec<String> = self.pointers().iter().map(|ptr| ptr.name.clone()).collect();
+		names.sort();
+		
+		names
+	}
+	
+	/// #### find
+	/// Returns the highest priority archive containing the entry.
+	fn find(&self, name: &str) -> Option<&(dyn Archive + Send + Sync)> {
+		self.archives.iter().rev().find(|archive| archive.pointer(name).is_some()).map(|archive| archive.as_ref())
+	}
+	
+}
+impl Archive for Vfs {
+	fn pointers(&self) -> Vec<&Pointer> {
+		let mut pointers: HashMap<&str, &Pointer> = HashMap::new();
+		for archive in self.archives.iter() {
+			for ptr in archive.pointers() { pointers.insert(&ptr.name, ptr); }
+		}
+		for (_, ptr) in self.loose.values() { pointers.insert(&ptr.name, ptr); }
+		
+		pointers.into_values().collect()
+	}
+	fn pointer(&self, name: &str) -> Option<&Pointer> {
+		if let Some((_, ptr)) = self.loose.get(name) { return Some(ptr) }
+		
+		self.find(name)?.pointer(name)
+	}
+	fn read(&self, name: &str) -> Result<Cow<'_, [u8]>, BulkError> {
+		if let Some((path, _)) = self.loose.get(name) {
+			return fs::read(path).map(Cow::Owned).map_err(|_| BulkError::FailedToRead)
+		}
+		
+		self.find(name).ok_or(BulkError::EntryNotFound(name.to_string()))?.read(name)
+	}
+}
+
+/// #### scan
+/// Recursively adds every file in the directory to the list of loose files, in order of path.
+/// 
+/// Names already taken by another file are added to the collisions instead.
+fn scan(root: &Path, directory: &Path, loose: &mut HashMap<String, (PathBuf, Pointer)>, collisions: &mut Vec<String>) {
+	let Ok(entries) = fs::read_dir(directory) else { return };
+	let mut entries: Vec<fs::DirEntry> = entries.flatten().collect();
+	entries.sort_by_key(|entry| entry.path());
+	
+	for entry in entries {
+		let path = entry.path();
+		if path.is_dir() {
+			scan(root, &path, loose, collisions);
+			continue
+		}
+		
+		let Ok(relative) = path.strip_prefix(root) else { continue };
+		let name = relative.with_extension("").to_string_lossy().replace('\\', "/");
+		if loose.contains_key(&name) {
+			if !collisions.contains(&name) { collisions.push(name); }
+			continue
+		}
+		let size = entry.metadata().map(|m| m.len() as usize).unwrap_or(0);
+		let extension = path.extension().unwrap_or_default().to_string_lossy().to_string();
+		
+		let ptr = Pointer {
+			name: name.clone(),
+			size,
+			raw_size: size,
+			file_type: FileType::from_extension(&extension),
+			..Default::default()
+		};
+		loose.insert(name, (path, ptr));
+	}
+}
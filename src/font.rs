@@ -46,6 +46,18 @@ pub struct FontRl {
     pub glyphs:	   *mut GlyphInfo,
 }
 
+/// #### FontAtlas
+/// Glyphs and atlas of a font rasterized on the CPU, waiting for the atlas to be uploaded as its texture.
+#[derive(Debug, Clone, Copy)]
+pub struct FontAtlas {
+	pub base_size:		i32,
+	pub glyph_count:	i32,
+	pub glyph_padding:	i32,
+	pub atlas:			Image,
+	pub recs:	   *mut Rectangle,
+	pub glyphs:	   *mut GlyphInfo,
+}
+
 /// #### Raw raylib structure
 #[repr(C)]
 #[derive(Debug, Clone, Copy)]
@@ -128,6 +140,54 @@ impl Font {
 			}
 		}
 	}
+	/// #### decode_from_memory
+	/// Rasterizes the glyphs and atlas of a TTF or OTF font the way Raylib::LoadFontFromMemory does, without the GPU.
+	/// 
+	/// It can run on any thread, finish it with from_atlas on the main thread. Returns None if the data can't be read.
+	pub fn decode_from_memory(file_data: &[u8], font_size: i32) -> Option<FontAtlas> {
+		unsafe {
+			let glyph_count = 95;
+			let glyphs = LoadFontData(file_data.as_ptr(), file_data.len() as i32, font_size, null(), glyph_count, 0);
+			if glyphs.is_null() { return None }
+
+			let glyph_padding = 4;
+			let mut recs = std::ptr::null_mut();
+			let atlas = Image(GenImageFontAtlas(glyphs, &mut recs, glyph_count, font_size, glyph_padding, 0));
+			if recs.is_null() || !atlas.is_ready() {
+				UnloadFontData(glyphs, glyph_count);
+				return None
+			}
+
+			//* Glyph images are cut from the atlas, like Raylib does so they work with ImageDrawText */
+			for i in 0..glyph_count as usize {
+				let glyph = &mut *glyphs.add(i);
+				Image(glyph.image).unload();
+				glyph.image = atlas.from_image(*recs.add(i)).0;
+			}
+
+			Some(FontAtlas { base_size: font_size, glyph_count, glyph_padding, atlas, recs, glyphs })
+		}
+	}
+	/// #### from_atlas
+	/// Uploads the atlas of a font made by decode_from_memory as its texture, unloading the atlas image.
+	pub fn from_atlas(atlas: FontAtlas) -> Self {
+		let texture = atlas.atlas.texture();
+		atlas.atlas.unload();
+
+		Self {
+			data: FontRl {
+				base_size: atlas.base_size,
+				glyph_count: atlas.glyph_count,
+				glyph_padding: atlas.glyph_padding,
+				texture,
+				recs: atlas.recs,
+				glyphs: atlas.glyphs,
+			},
+			size: atlas.base_size as f32,
+			spacing: 1.0,
+			tint: BLACK,
+		}
+	}
 	/// #### unload
 	/// Wrapper for Raylib::UnloadFont(font: Font).
 	pub fn unload(&mut self) {
@@ -224,6 +284,9 @@ extern "C" { fn LoadFontFromImage(image: ImageRl, key: Color, firstChar: i32) ->
 extern "C" { fn LoadFontFromMemory(fileType: *const i8, fileData: *const u8, dataSize: i32, fontSize: i32, codepoints: *const i32, codepointCount: i32) -> FontRl; }
 extern "C" { fn IsFontReady(font: FontRl) -> bool; }
 extern "C" { fn UnloadFont(font: FontRl); }
+extern "C" { fn LoadFontData(fileData: *const u8, dataSize: i32, fontSize: i32, codepoints: *const i32, codepointCount: i32, fontType: i32) -> *mut GlyphInfo; }
+extern "C" { fn UnloadFontData(glyphs: *mut GlyphInfo, glyphCount: i32); }
+extern "C" { fn GenImageFontAtlas(glyphs: *const GlyphInfo, glyphRecs: *mut *mut Rectangle, glyphCount: i32, fontSize: i32, padding: i32, packMethod: i32) -> ImageRl; }
 
 //= Text drawing functions
 extern "C" { fn DrawTextEx(font: FontRl, text: *const i8, position: Vector2, font_size: f32, spacing: f32, tint: Color); }
@@ -0,0 +1,128 @@
+use std::{collections::HashMap, fmt::Display};
+
+use serde_json::{json, Value};
+
+use crate::pleroma::Pleroma;
+use super::{gamepad::*, keyboard::*, mouse::*, Device, Keybind};
+
+
+/// #### KeybindError
+/// Errors that can occur while saving or loading keybindings.
+#[derive(Debug, Clone, PartialEq)]
+pub enum KeybindError {
+	FailedToRead,
+	FailedToWrite,
+	Malformed,
+}
+impl Display for KeybindError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			KeybindError::FailedToRead	=> write!(f, "Failed to read keybinding file."),
+			KeybindError::FailedToWrite	=> write!(f, "Failed to write keybinding file."),
+			KeybindError::Malformed		=> write!(f, "Keybinding file is malformed."),
+		}
+	}
+}
+
+impl Keybind {
+	
+	/// #### to_json
+	/// Converts the Keybind into JSON, naming its device, modifier and key.
+	/// 
+	/// Codes without a name are saved as numbers.
+	pub fn to_json(&self) -> Value {
+		let modifier = if self.keys[0] == 0 { Value::Null } else { key_to_json(&self.device, self.keys[0]) };
+		let key = key_to_json(&self.device, self.keys[1]);
+		
+		match self.device {
+			Device::Keyboard	=> json!({ "device": "keyboard", "modifier": modifier, "key": key }),
+			Device::Mouse		=> json!({ "device": "mouse", "modifier": modifier, "key": key }),
+			Device::Gamepad(id)	=> json!({ "device": "gamepad", "gamepad": id, "modifier": modifier, "key": key }),
+		}
+	}
+	/// #### from_json
+	/// Reads a Keybind from JSON made by to_json. Keys can be names, ignoring case, or codes.
+	pub fn from_json(value: &Value) -> Option<Self> {
+		let device = match value.get("device")?.as_str()?.to_lowercase().as_str() {
+			"keyboard"	=> Device::Keyboard,
+			"mouse"		=> Device::Mouse,
+			"gamepad"	=> Device::Gamepad(value.get("gamepad").and_then(Value::as_i64).unwrap_or(0) as i32),
+			_ => return None,
+		};
+		let modifier = match value.get("modifier") {
+			None | Some(Value::Null) => 0,
+			Some(modifier) => key_from_json(&device, modifier)?,
+		};
+		let key = key_from_json(&device, value.get("key")?)?;
+		
+		Some(Self { device, keys: [modifier, key] })
+	}
+	
+}
+
+/// #### keybinds_to_json
+/// Converts keybindings into a JSON object of actions and their Keybinds.
+pub fn keybinds_to_json(keybindings: &HashMap<String, Keybind>) -> Value {
+	Value::Object(keybindings.iter().map(|(name, keybind)| (name.clone(), keybind.to_json())).collect())
+}
+/// #### keybinds_from_json
+/// Reads keybindings from a JSON object of actions, starting from the defaults.
+/// 
+/// Actions missing from the JSON, or whose Keybind can't be read, keep their default.
+/// Returns None if the JSON isn't an object.
+pub fn keybinds_from_json(value: &Value, defaults: &HashMap<String, Keybind>) -> Option<HashMap<String, Keybind>> {
+	let mut keybindings = defaults.clone();
+	for (name, keybind) in value.as_object()? {
+		if let Some(keybind) = Keybind::from_json(keybind) { keybindings.insert(name.clone(), keybind); }
+	}
+	
+	Some(keybindings)
+}
+
+impl Pleroma {
+	
+	/// ### save_keybinds
+	/// Saves every keybinding to a JSON file, so remapped controls survive restarts.
+	pub fn save_keybinds(&self, filename: &str) -> Result<(), KeybindError> {
+		let text = serde_json::to_string_pretty(&keybinds_to_json(&self.keybindings)).map_err(|_| KeybindError::FailedToWrite)?;
+		
+		std::fs::write(filename, text).map_err(|_| KeybindError::FailedToWrite)
+	}
+	/// ### load_keybinds
+	/// Loads keybindings from a JSON file over the current ones.
+	/// 
+	/// Call it after adding the default keybindings, so actions the file doesn't have keep them.
+	/// Nothing changes if the file can't be read.
+	pub fn load_keybinds(&mut self, filename: &str) -> Result<&mut Self, KeybindError> {
+		let text = std::fs::read_to_string(filename).map_err(|_| KeybindError::FailedToRead)?;
+		let value: Value = serde_json::from_str(&text).map_err(|_| KeybindError::Malformed)?;
+		self.keybindings = keybinds_from_json(&value, &self.keybindings).ok_or(KeybindError::Malformed)?;
+		
+		Ok(self)
+	}
+	
+}
+
+/// #### key_to_json
+/// Converts a key code of the device into its name, or the code if it has none.
+fn key_to_json(device: &Device, code: i32) -> Value {
+	let name = match device {
+		Device::Keyboard	=> Some(KeyboardKey::from(code)).filter(|key| *key != KeyboardKey::Null || code == 0).map(|key| key.name()),
+		Device::Mouse		=> MouseButton::try_from(code).ok().map(|button| button.name()),
+		Device::Gamepad(_)	=> Some(GamepadButton::from(code)).filter(|button| *button != GamepadButton::Unknown || code == 0).map(|button| button.name()),
+	};
+	
+	name.map_or(json!(code), Value::String)
+}
+/// #### key_from_json
+/// Reads a key code of the device from its name or code.
+fn key_from_json(device: &Device, value: &Value) -> Option<i32> {
+	if let Some(code) = value.as_i64() { return Some(code as i32) }
+	
+	let name = value.as_str()?;
+	match device {
+		Device::Keyboard	=> KeyboardKey::from_name(name).map(Into::into),
+		Device::Mouse		=> MouseButton::from_name(name).map(Into::into),
+		Device::Gamepad(_)	=> GamepadButton::from_name(name).map(Into::into),
+	}
+}
@@ -2,7 +2,7 @@
 
 /// ### GamepadButton
 /// Gamepad Keys stolen from raylib
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum GamepadButton {
 	/// Unknown button, just for error checking
 	Unknown			= 0,
@@ -94,4 +94,52 @@ impl Into<i32> for GamepadButton {
 			GamepadButton::RightTrigger		=> { 27 }
 		}
 	}
+}
+impl From<i32> for GamepadButton {
+	fn from(value: i32) -> Self {
+		match value {
+			1   => { GamepadButton::LeftFaceUp }
+			2   => { GamepadButton::LeftFaceRight }
+			3   => { GamepadButton::LeftFaceDown }
+			4   => { GamepadButton::LeftFaceLeft }
+			5   => { GamepadButton::RightFaceUp }
+			6   => { GamepadButton::RightFaceRight }
+			7   => { GamepadButton::RightFaceDown }
+			8   => { GamepadButton::RightFaceLeft }
+			9   => { GamepadButton::LeftTrigger1 }
+			10  => { GamepadButton::LeftTrigger2 }
+			11  => { GamepadButton::RightTrigger1 }
+			12  => { GamepadButton::RightTrigger2 }
+			13  => { GamepadButton::MiddleLeft }
+			14  => { GamepadButton::Middle }
+			15  => { GamepadButton::MiddleRight }
+			16  => { GamepadButton::LeftThumb }
+			17  => { GamepadButton::RightThumb }
+			18  => { GamepadButton::LeftXp }
+			19  => { GamepadButton::LeftXn }
+			20  => { GamepadButton::LeftYp }
+			21  => { GamepadButton::LeftYn }
+			22  => { GamepadButton::RightXp }
+			23  => { GamepadButton::RightXn }
+			24  => { GamepadButton::RightYp }
+			25  => { GamepadButton::RightYn }
+			26  => { GamepadButton::LeftTrigger }
+			27  => { GamepadButton::RightTrigger }
+			_   => { GamepadButton::Unknown }
+		}
+	}
+}
+impl GamepadButton {
+	
+	/// ### name
+	/// Returns the name of the button, as saved in keybinding files.
+	pub fn name(&self) -> String {
+		format!("{self:?}")
+	}
+	/// ### from_name
+	/// Returns the button with the name, ignoring case.
+	pub fn from_name(name: &str) -> Option<Self> {
+		(0..=27).map(GamepadButton::from).find(|button| button.name().eq_ignore_ascii_case(name))
+	}
+	
 }
\ No newline at end of file
@@ -452,4 +452,26 @@ impl From<i32> for KeyboardKey {
 			_ => { KeyboardKey::Null }
 		}
 	}
+}
+impl KeyboardKey {
+	
+	/// ### name
+	/// Returns the name of the key, as saved in keybinding files.
+	pub fn name(&self) -> String {
+		format!("{self:?}")
+	}
+	/// ### from_name
+	/// Returns the key with the name, ignoring case.
+	pub fn from_name(name: &str) -> Option<Self> {
+		(0..=348).map(KeyboardKey::from).find(|key| key.name().eq_ignore_ascii_case(name))
+	}
+	/// ### is_modifier
+	/// Checks whether the key is a shift, control, alt or super key, which keybinds use as modifiers.
+	pub fn is_modifier(&self) -> bool {
+		matches!(self,
+			KeyboardKey::LeftShift | KeyboardKey::LeftControl | KeyboardKey::LeftAlt | KeyboardKey::LeftSuper |
+			KeyboardKey::RightShift | KeyboardKey::RightControl | KeyboardKey::RightAlt | KeyboardKey::RightSuper
+		)
+	}
+	
 }
\ No newline at end of file
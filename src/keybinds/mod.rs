@@ -5,15 +5,17 @@ use crate::{pleroma::Pleroma, vectors::*};
 pub mod keyboard;
 pub mod mouse;
 pub mod gamepad;
+pub mod config;
+pub mod rebind;
 
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Keybind {
 	pub device: Device,
 	pub keys: [i32;2],
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Device {
 	Keyboard,
 	Mouse,
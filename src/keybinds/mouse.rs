@@ -2,7 +2,7 @@
 
 /// ### Mouse Keys
 /// Stolen from raylib.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum MouseButton {
 	/// Mouse button left
 	Left	= 0,
@@ -49,4 +49,40 @@ impl Into<i32> for MouseButton {
 			MouseButton::Wheeln		=> { 12 }
 		}
 	}
+}
+impl TryFrom<i32> for MouseButton {
+	type Error = i32;
+	
+	fn try_from(value: i32) -> Result<Self, Self::Error> {
+		match value {
+			0  => { Ok(MouseButton::Left) }
+			1  => { Ok(MouseButton::Right) }
+			2  => { Ok(MouseButton::Middle) }
+			3  => { Ok(MouseButton::Side) }
+			4  => { Ok(MouseButton::Extra) }
+			5  => { Ok(MouseButton::Forward) }
+			6  => { Ok(MouseButton::Back) }
+			7  => { Ok(MouseButton::Xp) }
+			8  => { Ok(MouseButton::Xn) }
+			9  => { Ok(MouseButton::Yp) }
+			10 => { Ok(MouseButton::Yn) }
+			11 => { Ok(MouseButton::Wheelp) }
+			12 => { Ok(MouseButton::Wheeln) }
+			_  => { Err(value) }
+		}
+	}
+}
+impl MouseButton {
+	
+	/// ### name
+	/// Returns the name of the button, as saved in keybinding files.
+	pub fn name(&self) -> String {
+		format!("{self:?}")
+	}
+	/// ### from_name
+	/// Returns the button with the name, ignoring case.
+	pub fn from_name(name: &str) -> Option<Self> {
+		(0..=12).filter_map(|code| MouseButton::try_from(code).ok()).find(|button| button.name().eq_ignore_ascii_case(name))
+	}
+	
 }
\ No newline at end of file
@@ -0,0 +1,281 @@
+use std::collections::HashMap;
+
+use crate::{pleroma::Pleroma, vectors::*};
+use super::{keyboard::*, Device, Keybind};
+
+
+/// Number of gamepads listened to, as many as Raylib supports.
+pub const MAX_GAMEPADS: i32 = 4;
+
+/// #### ConflictPolicy
+/// What a rebind does when the new input is already bound to another action.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ConflictPolicy {
+	/// Refuses the input and keeps listening.
+	#[default]
+	Reject,
+	/// Gives the other actions the rebound action's old keybind.
+	Swap,
+	/// Binds it anyway, leaving both actions on the same input.
+	Allow,
+}
+
+/// #### RebindStatus
+/// Where the current or last rebind is at.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum RebindStatus {
+	/// No rebind has been started.
+	#[default]
+	Idle,
+	/// Waiting for the next input.
+	Listening,
+	/// The input was refused for being bound to the actions, and it's still listening.
+	Conflict { keybind: Keybind, actions: Vec<String> },
+	/// The keybind was written, with the actions that were already bound to it.
+	Bound { keybind: Keybind, conflicts: Vec<String> },
+	/// The rebind was cancelled, keeping the old keybind.
+	Cancelled,
+}
+
+/// #### Rebinder
+/// Listens for the next input from any device to bind an action to, for controls menus.
+#[derive(Debug, Clone)]
+pub struct Rebinder {
+	pub conflicts: ConflictPolicy,
+	/// Key that cancels the rebind instead of being bound, or Null for none.
+	pub cancel_key: KeyboardKey,
+	/// Pixels the mouse has to move in a frame to bind a mouse axis.
+	pub mouse_threshold: f32,
+	/// How far a gamepad axis has to move, from 0.0 to 1.0, to bind it.
+	pub axis_threshold: f32,
+	
+	action: Option<String>,
+	status: RebindStatus,
+	/// Modifier key held, bound alone if it's released before another key is pressed.
+	modifier: Option<i32>,
+	/// Gamepad axes past the threshold last frame as their gamepad and code, so held axes aren't bound.
+	held: Vec<(i32, i32)>,
+	/// Set until the first update after starting, so the input that started the rebind isn't bound.
+	starting: bool,
+}
+impl Default for Rebinder {
+	fn default() -> Self {
+		Self {
+			conflicts: ConflictPolicy::Reject,
+			cancel_key: KeyboardKey::Escape,
+			mouse_threshold: 8.0,
+			axis_threshold: 0.5,
+			action: None,
+			status: RebindStatus::Idle,
+			modifier: None,
+			held: Vec::new(),
+			starting: false,
+		}
+	}
+}
+impl Rebinder {
+	
+	/// #### start
+	/// Starts listening for a new keybind for the action, cancelling any rebind in progress.
+	pub fn start(&mut self, action: &str) -> &mut Self {
+		self.action = Some(action.to_string());
+		self.status = RebindStatus::Listening;
+		self.modifier = None;
+		self.starting = true;
+		
+		self
+	}
+	/// #### cancel
+	/// Stops listening without changing any keybind.
+	pub fn cancel(&mut self) -> &mut Self {
+		if self.action.take().is_some() { self.status = RebindStatus::Cancelled; }
+		self.modifier = None;
+		
+		self
+	}
+	/// #### listening
+	/// Checks whether a rebind is waiting for input.
+	pub fn listening(&self) -> bool {
+		self.action.is_some()
+	}
+	/// #### action
+	/// Returns the action being rebound.
+	pub fn action(&self) -> Option<&str> {
+		self.action.as_deref()
+	}
+	/// #### status
+	/// Returns where the current or last rebind is at.
+	pub fn status(&self) -> &RebindStatus {
+		&self.status
+	}
+	/// #### capture
+	/// Binds the action being rebound to the keybind, following the conflict policy, and returns the new status.
+	/// 
+	/// Does nothing if no rebind is listening.
+	pub fn capture(&mut self, keybind: Keybind, keybindings: &mut HashMap<String, Keybind>) -> &RebindStatus {
+		let Some(action) = self.action.clone() else { return &self.status };
+		
+		let mut conflicts: Vec<String> = keybindings.iter()
+			.filter(|(name, bound)| **name != action && **bound == keybind)
+			.map(|(name, _)| name.clone())
+			.collect();
+		conflicts.sort();
+		
+		match self.conflicts {
+			ConflictPolicy::Reject if !conflicts.is_empty() => {
+				self.status = RebindStatus::Conflict { keybind, actions: conflicts };
+				return &self.status
+			}
+			ConflictPolicy::Swap => {
+				if let Some(old) = keybindings.get(&action).cloned() {
+					for name in conflicts.iter() { keybindings.insert(name.clone(), old.clone()); }
+				}
+			}
+			_ => {}
+		}
+		keybindings.insert(action, keybind.clone());
+		self.action = None;
+		self.modifier = None;
+		self.status = RebindStatus::Bound { keybind, conflicts };
+		
+		&self.status
+	}
+	
+	/// #### poll
+	/// Returns the first new input this frame from any device as a Keybind.
+	/// 
+	/// Keyboard keys are bound with a modifier if one is held. Gamepad buttons are bound alone.
+	fn poll(&mut self) -> Option<Keybind> {
+		unsafe {
+			//* Keyboard, waiting on modifiers to see if they're held for another key */
+			let mut key = GetKeyPressed();
+			while key != 0 {
+				if KeyboardKey::from(key).is_modifier() {
+					self.modifier = Some(key);
+				} else {
+					let modifier = self.modifier.take().filter(|modifier| IsKeyDown(*modifier)).unwrap_or(0);
+					return Some(Keybind { device: Device::Keyboard, keys: [modifier, key] })
+				}
+				key = GetKeyPressed();
+			}
+			if let Some(modifier) = self.modifier.filter(|modifier| !IsKeyDown(*modifier)) {
+				self.modifier = None;
+				return Some(Keybind { device: Device::Keyboard, keys: [0, modifier] })
+			}
+			
+			//* Mouse buttons, wheel and movement */
+			if let Some(button) = (0..=6).find(|button| IsMouseButtonPressed(*button)) {
+				return Some(Keybind { device: Device::Mouse, keys: [0, button] })
+			}
+			let wheel = GetMouseWheelMove();
+			if wheel != 0.0 {
+				return Some(Keybind { device: Device::Mouse, keys: [0, if wheel > 0.0 { 11 } else { 12 }] })
+			}
+			let delta = GetMouseDelta();
+			if delta.x.abs() >= self.mouse_threshold && delta.x.abs() >= delta.y.abs() {
+				return Some(Keybind { device: Device::Mouse, keys: [0, if delta.x > 0.0 { 7 } else { 8 }] })
+			}
+			if delta.y.abs() >= self.mouse_threshold {
+				return Some(Keybind { device: Device::Mouse, keys: [0, if delta.y > 0.0 { 9 } else { 10 }] })
+			}
+			
+			//* Gamepad buttons, then axes that just passed the threshold */
+			for id in (0..MAX_GAMEPADS).filter(|id| IsGamepadAvailable(*id)) {
+				if let Some(button) = (1..=17).find(|button| IsGamepadButtonPressed(id, *button)) {
+					return Some(Keybind { device: Device::Gamepad(id), keys: [0, button] })
+				}
+			}
+			let axes = gamepad_axes(self.axis_threshold);
+			let moved = axes.iter().find(|axis| !self.held.contains(axis)).copied();
+			self.held = axes;
+			
+			moved.map(|(id, code)| Keybind { device: Device::Gamepad(id), keys: [0, code] })
+		}
+	}
+	
+}
+
+impl Pleroma {
+	
+	/// ### start_rebind
+	/// Starts listening for the next input from any device to bind the action to.
+	/// 
+	/// Input is read while drawing, following the rebinder's conflict policy. Check rebind_status for the result.
+	/// Keys already queued and input from the frame it starts on are ignored, so the press that opened it isn't bound.
+	pub fn start_rebind(&mut self, action: &str) -> &mut Self {
+		self.rebinder.start(action);
+		self.rebinder.held = gamepad_axes(self.rebinder.axis_threshold);
+		while unsafe{ GetKeyPressed() } != 0 {}
+		
+		self
+	}
+	/// ### cancel_rebind
+	/// Stops listening for a new keybind, keeping the old one.
+	pub fn cancel_rebind(&mut self) -> &mut Self {
+		self.rebinder.cancel();
+		
+		self
+	}
+	/// ### rebind_status
+	/// Returns where the current or last rebind is at.
+	pub fn rebind_status(&self) -> &RebindStatus {
+		self.rebinder.status()
+	}
+	/// ### update_rebind
+	/// Reads this frame's input for the rebind in progress, cancelling it if the cancel key was pressed.
+	pub fn update_rebind(&mut self) -> &mut Self {
+		if !self.rebinder.listening() { return self }
+		
+		//* Skip the frame the rebind started on */
+		if self.rebinder.starting {
+			self.rebinder.starting = false;
+			self.rebinder.held = gamepad_axes(self.rebinder.axis_threshold);
+			while unsafe{ GetKeyPressed() } != 0 {}
+			return self
+		}
+		
+		let cancel: i32 = self.rebinder.cancel_key.into();
+		if cancel != 0 && unsafe{ IsKeyPressed(cancel) } {
+			self.rebinder.cancel();
+		} else if let Some(keybind) = self.rebinder.poll() {
+			self.rebinder.capture(keybind, &mut self.keybindings);
+		}
+		
+		self
+	}
+	
+}
+
+/// #### gamepad_axes
+/// Returns the axes of every gamepad past the threshold, as their gamepad and code.
+fn gamepad_axes(threshold: f32) -> Vec<(i32, i32)> {
+	let mut axes = Vec::new();
+	for id in (0..MAX_GAMEPADS).filter(|id| unsafe{ IsGamepadAvailable(*id) }) {
+		for axis in 0..4 {
+			let movement = unsafe{ GetGamepadAxisMovement(id, axis) };
+			if movement >= threshold { axes.push((id, 18 + axis * 2)); }
+			if movement <= -threshold { axes.push((id, 19 + axis * 2)); }
+		}
+		//* Triggers rest at -1.0 */
+		for axis in 4..6 {
+			if unsafe{ GetGamepadAxisMovement(id, axis) } >= threshold { axes.push((id, 22 + axis)); }
+		}
+	}
+	
+	axes
+}
+
+//= Input-related functions: keyboard
+extern "C" { fn GetKeyPressed() -> i32; }
+extern "C" { fn IsKeyPressed(key: i32) -> bool; }
+extern "C" { fn IsKeyDown(key: i32) -> bool; }
+
+//= Input-related functions: gamepads
+extern "C" { fn IsGamepadAvailable(gamepad: i32) -> bool; }
+extern "C" { fn IsGamepadButtonPressed(gamepad: i32, button: i32) -> bool; }
+extern "C" { fn GetGamepadAxisMovement(gamepad: i32, axis: i32) -> f32; }
+
+//= Input-related functions: mouse
+extern "C" { fn IsMouseButtonPressed(button: i32) -> bool; }
+extern "C" { fn GetMouseDelta() -> Vector2; }
+extern "C" { fn GetMouseWheelMove() -> f32; }
@@ -29,6 +29,7 @@ pub mod g_2d;
 pub mod g_3d;
 pub mod keybinds;
 pub mod files;
+pub mod assets;
 pub mod camera;
 pub mod audio;
 pub mod debug;
\ No newline at end of file
@@ -1,4 +1,6 @@
 
+use std::{ffi::CStr, ptr::{copy_nonoverlapping, null_mut}, sync::Mutex};
+
 use crate::{
 	rl_str,
 	bounds::*,
@@ -175,6 +177,14 @@ impl ModelRl {
 	pub fn load(filename: &str) -> Self {
 		unsafe{ LoadModel(rl_str!(filename)) }
 	}
+	/// #### load_from_memory
+	/// Loads a model from the data of a file, using the extension in file_type (".obj", ".iqm", ".glb", ".vox" or ".m3d").
+	/// 
+	/// Raylib has no LoadModelFromMemory, so the data is handed to Raylib::LoadModel through the file data and text callbacks.
+	/// Files the model refers to, like OBJ materials or external glTF buffers, are still read from disk.
+	pub fn load_from_memory(file_type: &str, file_data: &[u8]) -> Self {
+		with_memory_file(file_type, file_data, |filename| unsafe{ LoadModel(rl_str!(filename)) })
+	}
 	/// #### ready
 	/// Wrapper for Raylib::LoadModel(model: Model) -> bool.
 	pub fn ready(&mut self) -> bool {
@@ -273,6 +283,11 @@ impl ModelAnimation {
 			result
 		}
 	}
+	/// #### load_from_memory
+	/// Loads animations from the data of a file, using the extension in file_type (".glb", ".iqm", ...).
+	pub fn load_from_memory(file_type: &str, file_data: &[u8]) -> Vec<Self> {
+		with_memory_file(file_type, file_data, Self::load)
+	}
 	///// #### unload
 	///// Wrapper for Raylib::UnloadModelAnimations(animations: *mut ModelAnimations, anim_count: i32);
 	//pub fn unload(animations: Vec<Self>) {}
@@ -293,6 +308,119 @@ impl ModelAnimation {
 }
 
 
+/// Raylib callback reading the data of a file.
+pub type LoadFileDataCallback = extern "C" fn(filename: *const i8, data_size: *mut i32) -> *mut u8;
+/// Raylib callback reading the text of a file.
+pub type LoadFileTextCallback = extern "C" fn(filename: *const i8) -> *mut i8;
+
+/// Held for the whole of a with_memory_file, so only one memory load swaps the callbacks at a time.
+static MEMORY_LOAD: Mutex<()> = Mutex::new(());
+/// Data served to Raylib by load_memory_file, with the name it's requested under.
+static MEMORY_FILE: Mutex<Option<(String, Vec<u8>)>> = Mutex::new(None);
+/// Callbacks installed through set_load_file_callbacks, restored after each memory load.
+static FILE_CALLBACKS: Mutex<(Option<LoadFileDataCallback>, Option<LoadFileTextCallback>)> = Mutex::new((None, None));
+
+/// #### set_load_file_callbacks
+/// Wrapper for Raylib::SetLoadFileDataCallback(callback) and Raylib::SetLoadFileTextCallback(callback).
+/// 
+/// Callbacks set through here keep working around models loaded from memory, which swap in their own while loading.
+pub fn set_load_file_callbacks(data: Option<LoadFileDataCallback>, text: Option<LoadFileTextCallback>) {
+	let _load = MEMORY_LOAD.lock().unwrap();
+	*FILE_CALLBACKS.lock().unwrap() = (data, text);
+	
+	unsafe{
+		SetLoadFileDataCallback(data);
+		SetLoadFileTextCallback(text);
+	}
+}
+
+/// #### with_memory_file
+/// Runs the loader with Raylib reading the data whenever it opens the returned filename.
+/// Any other file Raylib opens in the meantime (like external glTF buffers) goes to the previous callbacks, or the disk.
+/// 
+/// Memory loads wait on each other, so the loader can't load from memory itself.
+fn with_memory_file<T>(file_type: &str, file_data: &[u8], loader: impl FnOnce(&str) -> T) -> T {
+	let _load = MEMORY_LOAD.lock().unwrap();
+	let filename = format!("pleroma_memory{file_type}");
+	*MEMORY_FILE.lock().unwrap() = Some((filename.clone(), file_data.to_vec()));
+	
+	unsafe{
+		SetLoadFileDataCallback(Some(load_memory_file));
+		SetLoadFileTextCallback(Some(load_memory_text));
+	}
+	let result = loader(&filename);
+	let (data, text) = *FILE_CALLBACKS.lock().unwrap();
+	unsafe{
+		SetLoadFileDataCallback(data);
+		SetLoadFileTextCallback(text);
+	}
+	
+	*MEMORY_FILE.lock().unwrap() = None;
+	
+	result
+}
+/// #### memory_file
+/// Returns the data with_memory_file serves under the filename.
+fn memory_file(filename: &str) -> Option<Vec<u8>> {
+	MEMORY_FILE.lock().unwrap().as_ref().filter(|(name, _)| *name == filename).map(|(_, data)| data.clone())
+}
+/// #### load_memory_file
+/// Raylib file data callback for with_memory_file.
+extern "C" fn load_memory_file(filename: *const i8, data_size: *mut i32) -> *mut u8 {
+	let name = unsafe{ CStr::from_ptr(filename) }.to_string_lossy().to_string();
+	
+	let data = match memory_file(&name) {
+		Some(data) => data,
+		None => {
+			let previous = FILE_CALLBACKS.lock().unwrap().0;
+			if let Some(previous) = previous { return previous(filename, data_size) }
+			std::fs::read(&name).unwrap_or_default()
+		}
+	};
+	unsafe{
+		if data.is_empty() {
+			*data_size = 0;
+			return null_mut()
+		}
+		
+		let buffer = MemAlloc(data.len() as u32) as *mut u8;
+		copy_nonoverlapping(data.as_ptr(), buffer, data.len());
+		*data_size = data.len() as i32;
+		
+		buffer
+	}
+}
+/// #### load_memory_text
+/// Raylib file text callback for with_memory_file, which OBJ models are read through.
+extern "C" fn load_memory_text(filename: *const i8) -> *mut i8 {
+	let name = unsafe{ CStr::from_ptr(filename) }.to_string_lossy().to_string();
+	
+	let data = match memory_file(&name) {
+		Some(data) => data,
+		None => {
+			let previous = FILE_CALLBACKS.lock().unwrap().1;
+			if let Some(previous) = previous { return previous(filename) }
+			match std::fs::read(&name) {
+				Ok(data) => data,
+				Err(_) => return null_mut(),
+			}
+		}
+	};
+	unsafe{
+		//* Null terminated, as Raylib expects */
+		let buffer = MemAlloc(data.len() as u32 + 1) as *mut u8;
+		copy_nonoverlapping(data.as_ptr(), buffer, data.len());
+		*buffer.add(data.len()) = 0;
+		
+		buffer as *mut i8
+	}
+}
+
+//= Files management functions
+extern "C" { fn SetLoadFileDataCallback(callback: Option<LoadFileDataCallback>); }
+extern "C" { fn SetLoadFileTextCallback(callback: Option<LoadFileTextCallback>); }
+extern "C" { fn MemAlloc(size: u32) -> *mut std::os::raw::c_void; }
+
 //= Model animations loading/unloading functions
 extern "C" { fn LoadModelAnimations(filename: *const i8, animCount: *mut i32) -> *mut ModelAnimation; }
 extern "C" { fn UpdateModelAnimation(model: ModelRl, anim: ModelAnimation, frame: i32); }
@@ -1,15 +1,16 @@
-use std::{collections::HashMap, ops::BitXorAssign};
+use std::{collections::HashMap, ops::BitXorAssign, time::Duration};
 
 use bitflags::bitflags;
 
 use crate::{
-	audio::AudioHandler, debug::{
+	assets::{loader::*, Assets},
+	audio::{spatial::Listener, AudioHandler}, debug::{
 		self,
 		errors::*,
 		DebugFlags,
 		LogLevel,
 	},
-	keybinds::*,
+	keybinds::{rebind::Rebinder, *},
 	camera::*,
 	rl_str,
 	color::*,
@@ -42,10 +43,15 @@ pub struct Pleroma {
 
 	//* Input */
 	pub keybindings: HashMap<String, Keybind>,
+	pub rebinder: Rebinder,
 	
 	//* Audio */
 	pub audio: AudioHandler,
 
+	//* Assets */
+	pub assets: Assets,
+	pub loader: Loader,
+
 	//* Debug */
 	db_level: debug::LogLevel,
 	db_settings: debug::DebugFlags,
@@ -85,8 +91,12 @@ impl Default for Pleroma {
 			camera: Camera::default(),
 
 			keybindings: HashMap::new(),
+			rebinder: Rebinder::default(),
 			
 			audio: AudioHandler::default(),
+			
+			assets: Assets::default(),
+			loader: Loader::default(),
 
 			db_level: debug::LogLevel::Info,
 			db_settings: debug::DebugFlags::all(),
@@ -126,12 +136,15 @@ impl Pleroma {
 
 	//= System
 	/// #### close
-	/// Unloads the RenderTexture and tells Raylib to close the window.
+	/// Unloads the RenderTexture and every registered asset, then tells Raylib to close the window.
 	pub fn close(&mut self) {
 		//* Unload RenderTexture */
 		if self.render_texture.is_some() {
 			self.render_texture.unwrap().unload()
 		}
+		
+		//* Unload assets */
+		self.assets.unload_all();
 
 		//* Close Window */
 		unsafe { CloseWindow() }
@@ -447,9 +460,17 @@ impl Pleroma {
 	/// #### draw
 	/// Draws to the screen. Calling any code implemented in add_contents.
 	pub fn draw(&mut self, draw_contents: impl FnOnce(&mut Pleroma)) -> &mut Self {
-		//* Update music */
+		//* Update music, hearing positioned sounds from the camera */
+		self.audio.set_listener(Listener::from_camera(&self.camera));
 		self.audio.update();
 		
+		//* Listen for a new keybind */
+		self.update_rebind();
+		
+		//* Finish loading assets */
+		self.update_loader();
+		self.update_assets();
+		
 		//* Check if render texture exists */
 		if self.render_texture.is_none() {
 			self.log(PlError::RenderTextureDoesntExist);
@@ -525,6 +546,41 @@ impl Pleroma {
 		
 		self
 	}
+	/// #### update_loader
+	/// Finishes the assets read in the background within the loader's budget, logging any that failed.
+	pub fn update_loader(&mut self) -> &mut Self {
+		self.loader.update();
+		for name in self.loader.take_failed() { self.log_detail(PlError::AssetFailedToLoad, &format!("\"{name}\"")); }
+		
+		self
+	}
+	/// #### update_assets
+	/// Reloads assets whose files changed if hot reload is enabled, logging each reload.
+	pub fn update_assets(&mut self) -> &mut Self {
+		for (key, success) in self.assets.update() {
+			if success {
+				self.log_detail(PlError::AssetReloaded, &format!("\"{key}\""));
+			} else {
+				self.log_detail(PlError::AssetFailedToReload, &format!("\"{key}\""));
+			}
+		}
+		
+		self
+	}
+	/// #### set_hot_reload
+	/// Sets whether textures, fonts, sounds and shaders loaded through assets are reloaded when their files change.
+	/// 
+	/// Files are checked once a second.
+	pub fn set_hot_reload(&mut self, enabled: bool) -> &mut Self {
+		self.assets.set_hot_reload(enabled, Duration::from_secs(1));
+		
+		self
+	}
+	/// #### loading_progress
+	/// Returns the fraction of queued assets that have finished loading, from 0.0 to 1.0.
+	pub fn loading_progress(&self) -> f32 {
+		self.loader.progress()
+	}
 	/// #### draw_2d
 	/// Draw with 2D camera
 	pub fn draw_2d(&mut self, draw_contents: impl FnOnce(&mut Pleroma)) -> &mut Self {
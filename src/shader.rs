@@ -1,6 +1,6 @@
 
 
-use std::{collections::HashMap, os::raw::c_void};
+use std::{collections::HashMap, ffi::CString, os::raw::c_void, ptr::null};
 
 use crate::rl_str;
 
@@ -28,6 +28,25 @@ impl Shader {
 			}
 		}
 	}
+	/// #### load_from_memory
+	/// Wrapper for Raylib::LoadShaderFromMemory(vsCode: *const i8, fsCode: *const i8).
+	/// 
+	/// Passing None uses Raylib's default shader for that stage. Returns None if either code contains a NUL byte.
+	pub fn load_from_memory(vs_code: Option<&str>, fs_code: Option<&str>) -> Option<Self> {
+		let vs_code = vs_code.map(CString::new).transpose().ok()?;
+		let fs_code = fs_code.map(CString::new).transpose().ok()?;
+		
+		unsafe{
+			Some(Self {
+				shader: LoadShaderFromMemory(
+					vs_code.as_ref().map_or(null(), |code| code.as_ptr()),
+					fs_code.as_ref().map_or(null(), |code| code.as_ptr()),
+				),
+				locations: HashMap::new(),
+				attributes: HashMap::new(),
+			})
+		}
+	}
 	/// #### ready
 	/// Wrapper for Raylib::IsShaderReady(shader: ShaderRl) -> bool.
 	pub fn ready(&mut self) -> bool {
@@ -121,6 +140,7 @@ extern "C" { fn EndShaderMode(); }
 
 //= Shader management functions
 extern "C" { fn LoadShader(vsFilename: *const i8, fsFilename: *const i8) -> ShaderRl; }
+extern "C" { fn LoadShaderFromMemory(vsCode: *const i8, fsCode: *const i8) -> ShaderRl; }
 extern "C" { fn IsShaderReady(shader: ShaderRl) -> bool; }
 extern "C" { fn GetShaderLocation(shader: ShaderRl, uniformName: *const i8) -> i32; }
 //extern "C" { fn GetShaderLocationAttrib(shader: ShaderRl, atttribName: *const i8) -> i32; }
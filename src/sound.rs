@@ -1,5 +1,7 @@
 
 
+use std::{ptr::null_mut, sync::Mutex};
+
 use crate::rl_str;
 
 
@@ -11,6 +13,21 @@ pub struct Sound {
 	stream: AudioStream,
 	frame_count: u32,
 }
+impl Default for Sound {
+	/// Empty Sound that isn't ready and plays nothing.
+	fn default() -> Self {
+		Self {
+			stream: AudioStream {
+				buffer: null_mut(),
+				processor: null_mut(),
+				sample_rate: 0,
+				sample_size: 0,
+				channels: 0,
+			},
+			frame_count: 0,
+		}
+	}
+}
 
 impl Sound {
 	
@@ -19,11 +36,36 @@ impl Sound {
 	pub fn load(filename: &str) -> Self {
 		unsafe{ LoadSound(rl_str!(filename)) }
 	}
+	/// #### load_from_memory
+	/// Loads a Wave from the data of a file, using the extension in file_type (".wav", ".ogg", ...), and converts it to a Sound.
+	/// 
+	/// Returns None if the data can't be decoded.
+	pub fn load_from_memory(file_type: &str, file_data: &[u8]) -> Option<Self> {
+		let mut wave = Wave::load_from_memory(file_type, file_data);
+		if !wave.ready() { return None }
+		
+		let sound = wave.to_sound();
+		wave.unload();
+		
+		if sound.ready() { Some(sound) } else { None }
+	}
+	/// #### alias
+	/// Wrapper for Raylib::LoadSoundAlias(source: Sound).
+	/// 
+	/// The alias shares the sample data of the Sound but plays independently of it.
+	pub fn alias(&self) -> Self {
+		unsafe{ LoadSoundAlias(*self) }
+	}
 	/// #### unload
 	/// Wrapper for Raylib::UnloadSound(sound: Sound).
 	pub fn unload(&mut self) {
 		unsafe{ UnloadSound(*self) }
 	}
+	/// #### unload_alias
+	/// Wrapper for Raylib::UnloadSoundAlias(alias: Sound).
+	pub fn unload_alias(&mut self) {
+		unsafe{ UnloadSoundAlias(*self) }
+	}
 	/// #### ready
 	/// Wrapper for Raylib::IsSoundReady(sound: Sound) -> bool.
 	pub fn ready(&self) -> bool {
@@ -84,6 +126,11 @@ impl Sound {
 		
 		self 
 	}
+	/// #### stream
+	/// Returns the AudioStream the Sound plays through.
+	pub fn stream(&self) -> AudioStream {
+		self.stream
+	}
 	
 }
 
@@ -108,6 +155,22 @@ impl Music {
 	pub fn load(filename: &str) -> Self {
 		unsafe{ LoadMusicStream(rl_str!(filename)) }
 	}
+	/// #### load_from_memory
+	/// Wrapper for Raylib::LoadMusicStreamFromMemory(fileType: *const i8, data: *const u8, dataSize: i32).
+	/// 
+	/// Raylib streams from the data while playing, so it's kept alive until the Music is unloaded.
+	/// Returns None if the data can't be decoded, freeing it.
+	pub fn load_from_memory(file_type: &str, file_data: Vec<u8>) -> Option<Self> {
+		let music = unsafe{ LoadMusicStreamFromMemory(rl_str!(file_type), file_data.as_ptr(), file_data.len() as i32) };
+		if !music.ready() { return None }
+		
+		//* A context left by Music that was never unloaded can be reused, so its data is dropped here */
+		let mut data = MUSIC_DATA.lock().unwrap();
+		data.retain(|(ctx, _)| *ctx != music.ctx_data as usize);
+		data.push((music.ctx_data as usize, file_data));
+		
+		Some(music)
+	}
 	/// #### is_ready
 	/// Wrapper for Raylib::IsMusicReady(music: Music) -> bool.
 	pub fn ready(&self) -> bool {
@@ -122,6 +185,7 @@ impl Music {
 	/// Wrapper for Raylib::UnloadMusicStream(music: Music).
 	pub fn unload(self) {
 		unsafe{ UnloadMusicStream(self) }
+		if !self.ctx_data.is_null() { MUSIC_DATA.lock().unwrap().retain(|(ctx, _)| *ctx != self.ctx_data as usize); }
 	}
 	
 	/// #### play
@@ -187,6 +251,13 @@ impl Music {
 		
 		self
 	}
+	/// #### looping
+	/// Sets whether the Music starts over when it ends, which it does by default.
+	pub fn looping(&mut self, looping: bool) -> &mut Self {
+		self.looping = looping;
+		
+		self
+	}
 	/// #### length
 	/// Wrapper for Raylib::SetMusicVolume(music: Music) -> f32.
 	pub fn length(&mut self) -> f32 {
@@ -197,6 +268,11 @@ impl Music {
 	pub fn played(&mut self) -> f32 {
 		unsafe{ GetMusicTimePlayed(*self) }
 	}
+	/// #### stream
+	/// Returns the AudioStream the Music plays through.
+	pub fn stream(&self) -> AudioStream {
+		self.stream
+	}
 	
 }
 
@@ -220,6 +296,11 @@ impl Wave {
 	pub fn load(filename: &str) -> Self {
 		unsafe{ LoadWave(rl_str!(filename)) }
 	}
+	/// #### load_from_memory
+	/// Wrapper for Raylib::LoadWaveFromMemory(fileType: *const i8, fileData: *const u8, dataSize: i32) -> Wave.
+	pub fn load_from_memory(file_type: &str, file_data: &[u8]) -> Self {
+		unsafe{ LoadWaveFromMemory(rl_str!(file_type), file_data.as_ptr(), file_data.len() as i32) }
+	}
 	/// #### unload
 	/// Wrapper for Raylib::UnloadWave(wave: Wave).
 	pub fn unload(&mut self) {
@@ -259,11 +340,60 @@ impl Wave {
 		unsafe{ WaveFormat(self, sample_rate, sample_size, channels) }
 	}
 	
+	/// #### from_samples
+	/// Creates a 32-bit Wave from interleaved samples, allocated by Raylib so it can be unloaded like any other Wave.
+	pub fn from_samples(samples: &[f32], sample_rate: u32, channels: u32) -> Self {
+		unsafe {
+			let data = MemAlloc(std::mem::size_of_val(samples) as u32) as *mut f32;
+			if !data.is_null() { std::ptr::copy_nonoverlapping(samples.as_ptr(), data, samples.len()); }
+			
+			Self {
+				frame_count: samples.len() as u32 / channels.max(1),
+				sample_rate,
+				sample_size: 32,
+				channels,
+				data: data as *mut std::os::raw::c_void,
+			}
+		}
+	}
+	/// #### frame_count
+	/// Returns the number of frames in the Wave.
+	pub fn frame_count(&self) -> u32 {
+		self.frame_count
+	}
+	/// #### sample_rate
+	/// Returns the sample rate of the Wave.
+	pub fn sample_rate(&self) -> u32 {
+		self.sample_rate
+	}
+	/// #### channels
+	/// Returns the number of channels in the Wave.
+	pub fn channels(&self) -> u32 {
+		self.channels
+	}
+	/// #### samples
+	/// Wrapper for Raylib::LoadWaveSamples(wave: Wave) -> *mut f32, copying the interleaved samples out as floats from -1.0 to 1.0.
+	pub fn samples(&self) -> Vec<f32> {
+		unsafe {
+			let data = LoadWaveSamples(*self);
+			if data.is_null() { return Vec::new() }
+			
+			let samples = std::slice::from_raw_parts(data, (self.frame_count * self.channels) as usize).to_vec();
+			UnloadWaveSamples(data);
+			
+			samples
+		}
+	}
+	
 }
 
 
+/// Data of Music loaded from memory, keyed by the Music's context pointer.
+static MUSIC_DATA: Mutex<Vec<(usize, Vec<u8>)>> = Mutex::new(Vec::new());
+
+
 #[repr(C)]
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub struct AudioStream {
 	buffer: *mut AudioBufferRl,
 	processor: *mut AudioProcessorRl,
@@ -273,6 +403,101 @@ pub struct AudioStream {
 	channels: u32,
 }
 
+impl AudioStream {
+	
+	/// #### load
+	/// Wrapper for Raylib::LoadAudioStream(sampleRate: u32, sampleSize: u32, channels: u32) -> AudioStream.
+	pub fn load(sample_rate: u32, sample_size: u32, channels: u32) -> Self {
+		unsafe{ LoadAudioStream(sample_rate, sample_size, channels) }
+	}
+	/// #### set_buffer_size_default
+	/// Wrapper for Raylib::SetAudioStreamBufferSizeDefault(size: i32).
+	pub fn set_buffer_size_default(size: i32) {
+		unsafe{ SetAudioStreamBufferSizeDefault(size) }
+	}
+	/// #### unload
+	/// Wrapper for Raylib::UnloadAudioStream(stream: AudioStream).
+	pub fn unload(&mut self) {
+		unsafe{ UnloadAudioStream(*self) }
+	}
+	/// #### ready
+	/// Wrapper for Raylib::IsAudioStreamReady(stream: AudioStream) -> bool.
+	pub fn ready(&self) -> bool {
+		unsafe{ IsAudioStreamReady(*self) }
+	}
+	/// #### processed
+	/// Wrapper for Raylib::IsAudioStreamProcessed(stream: AudioStream) -> bool.
+	pub fn processed(&self) -> bool {
+		unsafe{ IsAudioStreamProcessed(*self) }
+	}
+	/// #### update
+	/// Wrapper for Raylib::UpdateAudioStream(stream: AudioStream, data: *const c_void, frameCount: i32).
+	pub fn update(&mut self, data: &[f32], frame_count: i32) -> &mut Self {
+		unsafe{ UpdateAudioStream(*self, data.as_ptr() as *const std::os::raw::c_void, frame_count) }
+		
+		self
+	}
+	/// #### play
+	/// Wrapper for Raylib::PlayAudioStream(stream: AudioStream).
+	pub fn play(&mut self) -> &mut Self {
+		unsafe{ PlayAudioStream(*self) }
+		
+		self
+	}
+	/// #### pause
+	/// Wrapper for Raylib::PauseAudioStream(stream: AudioStream).
+	pub fn pause(&mut self) -> &mut Self {
+		unsafe{ PauseAudioStream(*self) }
+		
+		self
+	}
+	/// #### resume
+	/// Wrapper for Raylib::ResumeAudioStream(stream: AudioStream).
+	pub fn resume(&mut self) -> &mut Self {
+		unsafe{ ResumeAudioStream(*self) }
+		
+		self
+	}
+	/// #### stop
+	/// Wrapper for Raylib::StopAudioStream(stream: AudioStream).
+	pub fn stop(&mut self) -> &mut Self {
+		unsafe{ StopAudioStream(*self) }
+		
+		self
+	}
+	/// #### volume
+	/// Wrapper for Raylib::SetAudioStreamVolume(stream: AudioStream, volume: f32).
+	pub fn volume(&mut self, volume: f32) -> &mut Self {
+		unsafe{ SetAudioStreamVolume(*self, volume) }
+		
+		self
+	}
+	/// #### attach_processor
+	/// Wrapper for Raylib::AttachAudioStreamProcessor(stream: AudioStream, processor: AudioCallback).
+	pub fn attach_processor(&mut self, processor: AudioCallback) -> &mut Self {
+		unsafe{ AttachAudioStreamProcessor(*self, processor) }
+		
+		self
+	}
+	/// #### detach_processor
+	/// Wrapper for Raylib::DetachAudioStreamProcessor(stream: AudioStream, processor: AudioCallback).
+	pub fn detach_processor(&mut self, processor: AudioCallback) -> &mut Self {
+		unsafe{ DetachAudioStreamProcessor(*self, processor) }
+		
+		self
+	}
+	
+	/// #### sample_rate
+	/// Returns the sample rate of the stream.
+	pub fn sample_rate(&self) -> u32 {
+		self.sample_rate
+	}
+	
+}
+
+/// Callback Raylib passes stereo 32-bit float frames to, to process them in place.
+pub type AudioCallback = extern "C" fn(buffer_data: *mut std::os::raw::c_void, frames: u32);
+
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
 pub struct AudioBufferRl {
@@ -287,12 +512,15 @@ pub struct AudioProcessorRl {
 
 
 extern "C" { fn LoadWave(filename: *const i8) -> Wave; }
+extern "C" { fn LoadWaveFromMemory(fileType: *const i8, fileData: *const u8, dataSize: i32) -> Wave; }
 extern "C" { fn IsWaveReady(wave: Wave) -> bool; }
 extern "C" { fn LoadSound(filename: *const i8) -> Sound; }
 extern "C" { fn LoadSoundFromWave(wave: Wave) -> Sound; }
 extern "C" { fn IsSoundReady(sound: Sound) -> bool; }
 extern "C" { fn UnloadWave(wave: Wave); }
 extern "C" { fn UnloadSound(sound: Sound); }
+extern "C" { fn LoadSoundAlias(source: Sound) -> Sound; }
+extern "C" { fn UnloadSoundAlias(alias: Sound); }
 extern "C" { fn ExportWave(wave: Wave, filename: *const i8); }
 
 extern "C" { fn PlaySound(sound: Sound); }
@@ -305,9 +533,12 @@ extern "C" { fn SetSoundPitch(sound: Sound, pitch: f32); }
 extern "C" { fn SetSoundPan(sound: Sound, pan: f32); }
 extern "C" { fn WaveCopy(wave: Wave) -> Wave; }
 extern "C" { fn WaveCrop(wave: *mut Wave, init_sample: i32, final_sample: i32); }
+extern "C" { fn LoadWaveSamples(wave: Wave) -> *mut f32; }
+extern "C" { fn UnloadWaveSamples(samples: *mut f32); }
 extern "C" { fn WaveFormat(wave: *mut Wave, sample_rate: i32, sample_size: i32, channels: i32); }
 
 extern "C" { fn LoadMusicStream(filename: *const i8) -> Music; }
+extern "C" { fn LoadMusicStreamFromMemory(fileType: *const i8, data: *const u8, dataSize: i32) -> Music; }
 extern "C" { fn IsMusicReady(music: Music) -> bool; }
 extern "C" { fn UnloadMusicStream(music: Music); }
 extern "C" { fn PlayMusicStream(music: Music); }
@@ -322,3 +553,18 @@ extern "C" { fn SetMusicPitch(music: Music, pitch: f32); }
 extern "C" { fn SetMusicPan(music: Music, pan: f32); }
 extern "C" { fn GetMusicTimeLength(music: Music) -> f32; }
 extern "C" { fn GetMusicTimePlayed(music: Music) -> f32; }
+
+extern "C" { fn LoadAudioStream(sampleRate: u32, sampleSize: u32, channels: u32) -> AudioStream; }
+extern "C" { fn IsAudioStreamReady(stream: AudioStream) -> bool; }
+extern "C" { fn UnloadAudioStream(stream: AudioStream); }
+extern "C" { fn UpdateAudioStream(stream: AudioStream, data: *const std::os::raw::c_void, frameCount: i32); }
+extern "C" { fn IsAudioStreamProcessed(stream: AudioStream) -> bool; }
+extern "C" { fn PlayAudioStream(stream: AudioStream); }
+extern "C" { fn StopAudioStream(stream: AudioStream); }
+extern "C" { fn PauseAudioStream(stream: AudioStream); }
+extern "C" { fn ResumeAudioStream(stream: AudioStream); }
+extern "C" { fn SetAudioStreamVolume(stream: AudioStream, volume: f32); }
+extern "C" { fn SetAudioStreamBufferSizeDefault(size: i32); }
+extern "C" { fn AttachAudioStreamProcessor(stream: AudioStream, processor: AudioCallback); }
+extern "C" { fn DetachAudioStreamProcessor(stream: AudioStream, processor: AudioCallback); }
+extern "C" { fn MemAlloc(size: u32) -> *mut std::os::raw::c_void; }
@@ -5,14 +5,19 @@ use std::f32::consts::PI;
 
 //= Imports
 use pleroma::{
-	files::compression::CompressionType,
-	structures::{
-		color::*,
-		image::*,
-		matrix,
-		rectangle,
-		vectors::*,
-	},
+	assets::{loader::*, *},
+	audio::{beats::*, effects::*, midi::*, mixer::*, playlist::*, render::*, sfx::*, smf::*, spatial::*, synth::*, AudioHandler},
+	camera::*,
+	files::{compression::*, manifest::*, vfs::*, *},
+	color::*,
+	font::*,
+	image::*,
+	matrix,
+	rectangle,
+	sound::Sound,
+	texture::*,
+	vectors::*,
+	keybinds::{config::*, gamepad::*, keyboard::*, mouse::*, rebind::*, *},
 };
 
 
@@ -50,6 +55,1168 @@ fn compression() {
 	assert_eq!(data, gz_decomp);
 }
 
+/// Bulk
+#[test]
+fn bulk() {
+	let filename = std::env::temp_dir().join("pleroma_bulk.blk");
+	let filename = filename.to_str().unwrap();
+	
+	//* Build and save */
+	let mut bulk = Bulk::new();
+	bulk
+		.set_filename(filename)
+		.add("text", FileType::JSON, b"{\"value\": 10}".to_vec())
+		.add("blob", FileType::Image, vec![0, 1, 2, 3, 4, 5])
+		.add("empty", FileType::Model, Vec::new())
+		.add_compressed("packed", FileType::JSON, vec![7; 1024], CompressionType::ZLib, CompressionLevel::Best);
+	assert_eq!(bulk.save(), Ok(()), "Failed to save Bulk.");
+	
+	//* Load and get */
+	let loaded = Bulk::load(filename).expect("Failed to load Bulk.");
+	assert_eq!(loaded.table.len(), 4, "Bulk table size changed.");
+	assert_eq!(loaded.checksum, bulk.checksum, "Bulk checksum changed.");
+	assert_eq!(loaded.get("blob").as_deref(), Some([0u8, 1, 2, 3, 4, 5].as_slice()), "Bulk entry data changed.");
+	assert_eq!(loaded.get("empty").as_deref(), Some([].as_slice()), "Empty Bulk entry changed.");
+	assert_eq!(loaded.get("missing"), None, "Found entry that doesn't exist.");
+	
+	//* Compression */
+	assert_eq!(loaded.table["packed"].compression, CompressionType::ZLib, "Bulk entry compression changed.");
+	assert!(loaded.table["packed"].size < loaded.table["packed"].raw_size, "Bulk entry wasn't compressed.");
+	assert_eq!(loaded.get("packed").as_deref(), Some([7u8; 1024].as_slice()), "Failed to decompress Bulk entry.");
+	let packed = Pointer { name: "packed".to_string(), compression: CompressionType::ZLib, raw_size: 1024, ..Default::default() };
+	let failed = Err(BulkError::FailedToDecompress("packed".to_string()));
+	assert_eq!(packed.decompress(vec![1, 2, 3, 4]), failed, "Corrupt Bulk entry was decompressed.");
+	assert_eq!(packed.decompress(CompressionType::ZLib.compress(vec![7; 4096], CompressionLevel::Best)), failed, "Bulk entry larger than its raw size was decompressed.");
+	assert_eq!(packed.decompress(CompressionType::ZLib.compress(vec![7; 1024], CompressionLevel::Best)), Ok(vec![7; 1024]));
+	assert_eq!(loaded.table["blob"].file_type, FileType::Image, "Bulk entry type changed.");
+	assert_eq!(loaded.get_json("text").unwrap()["value"], 10, "Failed to parse JSON entry.");
+	assert_eq!(loaded.get_json("blob"), None, "Got JSON from entry of a different type.");
+	
+	//* Saving again produces the same file */
+	let original = std::fs::read(filename).unwrap();
+	let mut resaved = loaded;
+	assert_eq!(resaved.save(), Ok(()), "Failed to resave Bulk.");
+	assert_eq!(std::fs::read(filename).unwrap(), original, "Bulk format isn't stable.");
+	
+	//* Errors */
+	assert_eq!(Bulk::new().set_filename(filename).add("a_name_that_is_too_long", FileType::JSON, Vec::new()).save(), Err(BulkError::NameTooLong));
+	assert_eq!(Bulk::load("data/missing.blk").err(), Some(BulkError::FailedToOpen));
+	assert_eq!(Pointer { name: "a_name_that_is_too_long".to_string(), ..Default::default() }.to_bytes(), Err(BulkError::NameTooLong));
+	let pointer = Pointer { name: "ünïcödé".to_string(), ..Default::default() };
+	assert_eq!(Pointer::from_bytes(&pointer.to_bytes().unwrap()).unwrap().name, "ünïcödé", "Non-ASCII Bulk entry name changed.");
+	assert!(Pointer::from_bytes(&[0; 33]).is_none(), "Short Bulk entry was read.");
+	
+	//* Extensions for Raylib */
+	assert_eq!(extension(b"not a known format"), None, "Guessed the extension of unknown data.");
+	assert_eq!(extension(b"# cube\nv 0 0 0\nv 1 0 0\n"), Some(".obj"));
+	assert_eq!(extension(b"INTERQUAKEMODEL\0"), Some(".iqm"));
+	
+	//* Existing archive */
+	let test = Bulk::load("data/test.blk").expect("Failed to load data/test.blk.");
+	assert_eq!(test.get("test1").as_deref(), Some(b"test".as_slice()), "data/test.blk entry changed.");
+	
+	let _ = std::fs::remove_file(filename);
+}
+
+/// Bulk integrity
+#[test]
+fn bulk_integrity() {
+	let filename = std::env::temp_dir().join("pleroma_bulk_integrity.blk");
+	let filename = filename.to_str().unwrap();
+	
+	let mut bulk = Bulk::new();
+	bulk
+		.set_filename(filename)
+		.add("entry", FileType::JSON, vec![1; 32]);
+	assert_eq!(bulk.save(), Ok(()), "Failed to save Bulk.");
+	let original = std::fs::read(filename).unwrap();
+	assert_eq!(Bulk::load(filename).unwrap().verify(), Ok(()), "Intact Bulk failed verification.");
+	
+	//* Truncated table */
+	std::fs::write(filename, &original[..20]).unwrap();
+	assert_eq!(Bulk::load(filename).err(), Some(BulkError::TruncatedTable), "Truncated table wasn't detected.");
+	
+	//* Truncated data */
+	std::fs::write(filename, &original[..original.len() - 1]).unwrap();
+	assert_eq!(Bulk::load(filename).err(), Some(BulkError::EntryOutOfRange("entry".to_string())), "Truncated data wasn't detected.");
+	
+	//* Corrupted data */
+	let mut corrupted = original.clone();
+	*corrupted.last_mut().unwrap() = 0;
+	std::fs::write(filename, &corrupted).unwrap();
+	assert_eq!(Bulk::load(filename).err(), Some(BulkError::ChecksumMismatch), "Corrupted data wasn't detected.");
+	
+	//* Corrupted entry */
+	std::fs::write(filename, &original).unwrap();
+	let mut loaded = Bulk::load(filename).unwrap();
+	*loaded.data.last_mut().unwrap() = 0;
+	assert_eq!(loaded.read("entry").err(), Some(BulkError::CrcMismatch("entry".to_string())), "Corrupted entry wasn't detected.");
+	assert_eq!(loaded.get("entry"), None, "Got data from corrupted entry.");
+	assert_eq!(loaded.read("missing").err(), Some(BulkError::EntryNotFound("missing".to_string())));
+	
+	let _ = std::fs::remove_file(filename);
+}
+
+/// Manifest
+#[test]
+fn manifest() {
+	let filename = std::env::temp_dir().join("pleroma_manifest.mnf");
+	let filename = filename.to_str().unwrap();
+	
+	//* Split into volumes */
+	let mut bulk = Bulk::new();
+	bulk
+		.add("first", FileType::JSON, vec![1; 64])
+		.add("second", FileType::Image, vec![2; 64])
+		.add_compressed("third", FileType::Model, vec![3; 256], CompressionType::Deflate, CompressionLevel::Best);
+	assert_eq!(bulk.save_split(filename, 100), Ok(()), "Failed to save Manifest.");
+	
+	//* Load table and read entries */
+	let manifest = Manifest::load(filename).expect("Failed to load Manifest.");
+	assert_eq!(manifest.volumes.len(), 2, "Manifest wasn't split into volumes.");
+	assert_eq!(manifest.table.len(), 3, "Manifest table size changed.");
+	assert_eq!(manifest.table["second"].volume, 1, "Manifest entry in wrong volume.");
+	assert_eq!(manifest.get("first").as_deref(), Some([1u8; 64].as_slice()), "Manifest entry data changed.");
+	assert_eq!(manifest.get("second").as_deref(), Some([2u8; 64].as_slice()), "Manifest entry data changed.");
+	assert_eq!(manifest.get("third").as_deref(), Some([3u8; 256].as_slice()), "Failed to decompress Manifest entry.");
+	assert_eq!(manifest.get("missing"), None, "Found entry that doesn't exist.");
+	
+	//* Volume names have to fit their length in a byte */
+	let long = std::env::temp_dir().join(format!("{}.mnf", "v".repeat(300)));
+	assert_eq!(bulk.save_split(long.to_str().unwrap(), 100), Err(BulkError::VolumeNameTooLong));
+	
+	for i in 0..manifest.volumes.len() { let _ = std::fs::remove_file(manifest.volume_path(i)); }
+	let _ = std::fs::remove_file(filename);
+}
+
+/// Vfs
+#[test]
+fn vfs() {
+	let directory = std::env::temp_dir().join("pleroma_vfs");
+	let base_filename = directory.join("base.blk");
+	let patch_filename = directory.join("patch.blk");
+	let loose = directory.join("loose");
+	let _ = std::fs::create_dir_all(loose.join("sprites"));
+	
+	//* Base and patch archives */
+	let mut base = Bulk::new();
+	base
+		.set_filename(base_filename.to_str().unwrap())
+		.add("config", FileType::JSON, b"base".to_vec())
+		.add("level", FileType::JSON, b"base".to_vec())
+		.add("sprites/player", FileType::Image, b"base".to_vec());
+	assert_eq!(base.save(), Ok(()), "Failed to save base Bulk.");
+	let mut patch = Bulk::new();
+	patch
+		.set_filename(patch_filename.to_str().unwrap())
+		.add("level", FileType::JSON, b"patch".to_vec())
+		.add("new", FileType::JSON, b"patch".to_vec());
+	assert_eq!(patch.save(), Ok(()), "Failed to save patch Bulk.");
+	std::fs::write(loose.join("sprites").join("player.png"), b"loose").unwrap();
+	
+	//* Priority */
+	let mut vfs = Vfs::new();
+	vfs
+		.mount_bulk(base_filename.to_str().unwrap()).unwrap()
+		.mount_bulk(patch_filename.to_str().unwrap()).unwrap()
+		.mount_directory(loose.to_str().unwrap());
+	assert_eq!(vfs.get("config").as_deref(), Some(b"base".as_slice()), "Failed to read base entry.");
+	assert_eq!(vfs.get("level").as_deref(), Some(b"patch".as_slice()), "Patch didn't override base entry.");
+	assert_eq!(vfs.get("new").as_deref(), Some(b"patch".as_slice()), "Failed to read patch entry.");
+	assert_eq!(vfs.get("sprites/player").as_deref(), Some(b"loose".as_slice()), "Loose file didn't override archives.");
+	assert_eq!(vfs.pointer("sprites/player").unwrap().file_type, FileType::Image, "Loose file type wasn't guessed.");
+	assert_eq!(FileType::from_extension("JSON"), FileType::JSON);
+	assert_eq!(FileType::from_extension("txt"), FileType::Raw, "Unknown extension was guessed as a known type.");
+	assert_eq!(FileType::from(u8::from(FileType::Raw)), FileType::Raw);
+	assert_eq!(vfs.names(), vec!["config", "level", "new", "sprites/player"], "Vfs names changed.");
+	assert_eq!(vfs.read("missing").err(), Some(BulkError::EntryNotFound("missing".to_string())));
+	assert!(vfs.collisions().is_empty());
+	
+	//* Loose files that only differ by extension collide, the first path winning */
+	std::fs::write(loose.join("sprites").join("player.ogg"), b"sound").unwrap();
+	vfs.rescan();
+	assert_eq!(vfs.collisions(), ["sprites/player".to_string()]);
+	assert_eq!(vfs.get("sprites/player").as_deref(), Some(b"sound".as_slice()));
+	
+	let _ = std::fs::remove_dir_all(directory);
+}
+
+/// Midi
+#[test]
+fn midi() {
+	let value = serde_json::json!({
+		"looping": true,
+		"bpm": 120,
+		"instruments": [],
+		"data": [ [[0, 4, 1.0, 0.5], 0], [[0, 2, 1.5, 0.5], 6] ],
+	});
+	let midi = Midi::from_json(&value, |_| None).unwrap();
+	assert_eq!(midi.bpm, 120);
+	assert_eq!(midi.length, 2);
+	assert_eq!(midi.end, 7);
+	assert_eq!(midi.track[1].0.pitch, 1.5);
+	
+	//* Missing instruments and malformed data */
+	let mut missing = value.clone();
+	missing["instruments"] = serde_json::json!(["piano"]);
+	assert!(Midi::from_json(&missing, |_| None).is_none());
+	let mut malformed = value.clone();
+	malformed["data"] = serde_json::json!([ [[0, 4], 0] ]);
+	assert!(Midi::from_json(&malformed, |_| None).is_none());
+	
+	//* Loading files */
+	let filename = std::env::temp_dir().join("pleroma_midi.json");
+	let filename = filename.to_str().unwrap();
+	let mut audio = AudioHandler::default();
+	assert_eq!(audio.load_midi("data/missing.json", 0).err(), Some(MidiError::FailedToOpen));
+	std::fs::write(filename, "not json").unwrap();
+	assert_eq!(audio.load_midi(filename, 0).err(), Some(MidiError::NotJson), "Malformed JSON should be an error.");
+	std::fs::write(filename, malformed.to_string()).unwrap();
+	assert_eq!(audio.load_midi(filename, 0).err(), Some(MidiError::Malformed));
+	let mut unloaded = value.clone();
+	unloaded["instruments"] = serde_json::json!(["data/sounds/missing.wav"]);
+	std::fs::write(filename, unloaded.to_string()).unwrap();
+	assert_eq!(audio.load_midi(filename, 0).err(), Some(MidiError::Malformed), "Instruments that fail to load should be an error.");
+	assert!(audio.load_midi("data/doom.json", 0).is_ok());
+	assert_eq!(audio.load_midi("data/doom.json", 5).err(), Some(MidiError::NoSuchTrack(5)));
+	let _ = std::fs::remove_file(filename);
+	let mut archive = Bulk::new();
+	archive.add("track", FileType::JSON, b"not json".to_vec());
+	assert_eq!(audio.load_midi_from_archive(&archive, "missing", 0).err(), Some(MidiError::FailedToOpen));
+	assert_eq!(audio.load_midi_from_archive(&archive, "track", 0).err(), Some(MidiError::NotJson));
+	assert_eq!(audio.load_midi_from_archive(&archive, "track", 5).err(), Some(MidiError::NoSuchTrack(5)));
+	
+	//* Playback at 60 bpm and 4 ticks per beat, 4 ticks a second */
+	let mut slow = value.clone();
+	slow["bpm"] = serde_json::json!(60);
+	let mut midi = Midi::from_json(&slow, |_| None).unwrap();
+	assert_eq!(midi.advance(0.1), vec![0]);
+	assert_eq!(midi.advance(1.0), Vec::<usize>::new());
+	assert_eq!(midi.advance(0.5), vec![1]);
+	assert_eq!(midi.advance(0.25), vec![0], "Looping tracks should start over at the end.");
+	assert!((midi.tick - 0.4).abs() < 1e-6);
+	
+	//* Framerate independence */
+	let mut fast = Midi::from_json(&slow, |_| None).unwrap();
+	let mut notes = Vec::new();
+	for _ in 0..288 { notes.extend(fast.advance(1.0 / 144.0)); }
+	assert_eq!(notes, vec![0, 1, 0]);
+	assert!((fast.tick - 1.0).abs() < 1e-4);
+	
+	//* Tempo changes halfway, to 30 bpm */
+	let mut midi = Midi::from_json(&slow, |_| None).unwrap();
+	midi.tempo = vec![(4, 2_000_000)];
+	midi.advance(1.5);
+	assert!((midi.tick - 5.0).abs() < 1e-6);
+	
+	//* Stops at the end when not looping */
+	let mut midi = Midi::from_json(&slow, |_| None).unwrap();
+	midi.looping = false;
+	assert_eq!(midi.advance(2.0), vec![0, 1]);
+	assert!(!midi.is_on);
+	assert!(midi.advance(1.0).is_empty());
+}
+
+/// Midi voices
+#[test]
+fn midi_voices() {
+	let value = serde_json::json!({
+		"looping": false,
+		"bpm": 60,
+		"release": 0.5,
+		"instruments": ["sample"],
+		"data": [ [[0, 4, 1.0, 0.5], 0], [[0, 8, 1.0, 0.5], 1], [[0, 8, 1.0, 0.5], 2] ],
+	});
+	let mut midi = Midi::from_json(&value, |_| Some(Default::default())).unwrap();
+	midi.voices = vec![vec![Voice::default(); 2]];
+	
+	//* Overlapping notes use separate voices */
+	midi.update(0.1, 1.0);
+	assert!(midi.voices[0][0].active);
+	assert!(!midi.voices[0][1].active);
+	midi.update(0.2, 1.0);
+	assert!(midi.voices[0].iter().all(|voice| voice.active));
+	assert!((midi.voices[0][0].remaining - 0.8).abs() < 1e-6);
+	assert_eq!(midi.voices[0][1].remaining, 2.0);
+	
+	//* With every voice busy the oldest is taken over */
+	midi.update(0.25, 1.0);
+	assert_eq!(midi.voices[0][0].age, 0.0);
+	assert_eq!(midi.voices[0][0].remaining, 2.0);
+	assert!(midi.voices[0][1].age > 0.0);
+	
+	//* Notes fade for the release after their duration, then stop */
+	let mut midi = Midi::from_json(&value, |_| Some(Default::default())).unwrap();
+	midi.voices = vec![vec![Voice::default(); 3]];
+	midi.update(0.1, 1.0);
+	midi.update(1.2, 1.0);
+	assert!(midi.voices[0][0].active, "Released notes should keep fading.");
+	midi.update(0.3, 1.0);
+	assert!(!midi.voices[0][0].active);
+}
+
+/// Midi mixing
+#[test]
+fn midi_mixing() {
+	let value = serde_json::json!({
+		"bpm": 60,
+		"looping": true,
+		"instruments": [ { "waveform": "square" } ],
+		"data": [ [[0, 2, 1.0, 0.5], 0], [[0, 2, 1.0, 0.5], 2] ],
+	});
+	let volume = |midi: &Midi| midi.synth.as_ref().unwrap().volume;
+	
+	//* Track volume and muting, at 4 ticks a second */
+	let mut midi = Midi::from_json(&value, |_| None).unwrap();
+	midi.volume = 0.5;
+	midi.update(0.0, 0.8);
+	assert_eq!(volume(&midi), 0.4);
+	midi.muted = true;
+	midi.update(0.25, 0.8);
+	assert_eq!(volume(&midi), 0.0);
+	assert_eq!(midi.tick, 1.0, "Muted tracks should keep playing.");
+	midi.muted = false;
+	
+	//* Pausing */
+	midi.pause();
+	midi.update(1.0, 0.8);
+	assert_eq!(midi.tick, 1.0);
+	midi.resume();
+	midi.update(0.25, 0.8);
+	assert_eq!(midi.tick, 2.0);
+	
+	//* Layers fading out and back in */
+	midi.enabled = false;
+	midi.fade = 2.0;
+	midi.update(1.0, 1.0);
+	assert_eq!(midi.level, 0.5);
+	assert_eq!(volume(&midi), 0.25);
+	midi.update(2.0, 1.0);
+	assert_eq!(midi.level, 0.0);
+	midi.enabled = true;
+	midi.update(0.5, 1.0);
+	assert_eq!(midi.level, 0.25);
+	
+	//* Soloing and master volume through the handler */
+	let mut audio = AudioHandler::default();
+	*audio.track_mut(0) = Midi::from_json(&value, |_| None).unwrap();
+	*audio.track_mut(1) = Midi::from_json(&value, |_| None).unwrap();
+	audio.set_mode(AudioMode::Midi);
+	assert_eq!(volume(audio.track(0)), 0.8 * 0.6);
+	audio.solo_track(1, true);
+	assert_eq!(volume(audio.track(0)), 0.0);
+	assert_eq!(volume(audio.track(1)), 0.8 * 0.6);
+	audio.solo_track(1, false).set_master_volume(0.5);
+	assert_eq!(volume(audio.track(0)), 0.5 * 0.6);
+	audio.mute_track(0, true);
+	assert_eq!(volume(audio.track(0)), 0.0);
+	assert_eq!(volume(audio.track(1)), 0.5 * 0.6);
+}
+
+/// Mixer
+#[test]
+fn mixer() {
+	let mut mixer = Mixer::new();
+	assert_eq!(mixer.buses(), vec!["master", "music", "sfx", "ui", "voice"]);
+	
+	//* Buses play through master */
+	mixer.set_volume("master", 0.5).set_volume("sfx", 0.5);
+	assert_eq!(mixer.gain("master"), 0.5);
+	assert_eq!(mixer.gain("sfx"), 0.25);
+	assert_eq!(mixer.gain("ui"), 0.5);
+	mixer.add_bus("footsteps").volume = 0.5;
+	assert_eq!(mixer.gain("footsteps"), 0.25);
+	mixer.mute("footsteps", true);
+	assert_eq!(mixer.gain("footsteps"), 0.0);
+	assert_eq!(mixer.volume("footsteps"), 0.5);
+	mixer.mute("master", true);
+	assert_eq!(mixer.gain("sfx"), 0.0);
+	mixer.mute("master", false);
+	
+	//* Music ducks under voice */
+	mixer.set_volume("music", 1.0);
+	mixer.ducking = Some(Ducking { attack: 0.1, release: 0.7, level: 0.3, ..Default::default() });
+	assert!(!mixer.update(1.0, false));
+	assert!(mixer.update(0.05, true));
+	assert!((mixer.duck_level() - 0.65).abs() < 1e-6);
+	mixer.update(1.0, true);
+	assert_eq!(mixer.duck_level(), 0.3);
+	assert!((mixer.gain("music") - 0.15).abs() < 1e-6);
+	assert_eq!(mixer.gain("sfx"), 0.25, "Only the target bus should be ducked.");
+	mixer.update(0.35, false);
+	assert!((mixer.duck_level() - 0.65).abs() < 1e-6);
+	mixer.update(1.0, false);
+	assert_eq!(mixer.duck_level(), 1.0);
+	
+	//* No ducking */
+	mixer.update(1.0, true);
+	mixer.ducking = None;
+	assert!(mixer.update(0.0, true));
+	assert_eq!(mixer.gain("music"), 0.5);
+	
+	//* Handler volumes go through the buses */
+	let mut audio = AudioHandler::default();
+	audio.set_sfx_volume(0.25).add_bus("footsteps");
+	assert_eq!(audio.bus_volume("sfx"), 0.25);
+	assert_eq!(audio.mixer().gain("sfx"), 0.8 * 0.25);
+	assert_eq!(audio.mixer().gain("footsteps"), 0.8);
+	assert_eq!(audio.sfx_bus("missing"), None);
+}
+
+/// Playlist
+#[test]
+fn playlist() {
+	//* Loop regions, with the time played past the end kept */
+	let song = Song::new("battle.ogg").with_loop(10.0, Some(70.0));
+	assert_eq!(song.loop_seek(5.0, 90.0), None, "The intro should play before looping.");
+	assert_eq!(song.loop_seek(69.5, 90.0), None);
+	assert_eq!(song.loop_seek(70.25, 90.0), Some(10.25));
+	assert_eq!(Song::new("battle.ogg").with_loop(10.0, None).loop_seek(90.0, 90.0), Some(10.0));
+	assert_eq!(Song::new("battle.ogg").loop_seek(90.0, 90.0), None);
+	
+	//* Repeating in order */
+	let songs: Vec<Song> = ["a", "b", "c"].iter().map(|name| Song::new(name)).collect();
+	let name = |song: Option<&Song>| song.map(|song| song.filename.clone());
+	let mut playlist = Playlist::new(songs.clone());
+	assert_eq!(name(playlist.start()), Some("a".to_string()));
+	assert_eq!(name(playlist.next_song()), Some("b".to_string()));
+	assert_eq!(name(playlist.next_song()), Some("c".to_string()));
+	assert_eq!(name(playlist.next_song()), Some("a".to_string()));
+	assert_eq!(name(playlist.previous_song()), Some("a".to_string()));
+	playlist.repeat = Repeat::One;
+	assert_eq!(name(playlist.next_song()), Some("a".to_string()));
+	playlist.repeat = Repeat::Off;
+	playlist.next_song();
+	playlist.next_song();
+	assert_eq!(name(playlist.next_song()), None);
+	assert_eq!(name(playlist.current()), None);
+	assert_eq!(name(playlist.previous_song()), Some("c".to_string()));
+	
+	//* Shuffling plays every song once per cycle, never the same song twice in a row */
+	let songs: Vec<Song> = (0..8).map(|i| Song::new(&i.to_string())).collect();
+	let mut playlist = Playlist::new(songs.clone());
+	playlist.shuffle = true;
+	playlist.set_seed(42);
+	let mut played = vec![name(playlist.start()).unwrap()];
+	for _ in 0..31 { played.push(name(playlist.next_song()).unwrap()); }
+	for cycle in played.chunks(8) {
+		let mut sorted = cycle.to_vec();
+		sorted.sort();
+		assert_eq!(sorted, (0..8).map(|i| i.to_string()).collect::<Vec<String>>());
+	}
+	assert!(played.windows(2).all(|pair| pair[0] != pair[1]));
+	assert_ne!(played[..8], played[8..16]);
+	
+	let mut again = Playlist::new(songs);
+	again.shuffle = true;
+	again.set_seed(42);
+	let mut replayed = vec![name(again.start()).unwrap()];
+	for _ in 0..31 { replayed.push(name(again.next_song()).unwrap()); }
+	assert_eq!(played, replayed, "The same seed should shuffle the same way.");
+	
+	//* Crossfade levels */
+	let mut crossfade = Crossfade::new(None, 2.0);
+	assert_eq!(crossfade.levels(), (1.0, 0.0));
+	crossfade.elapsed = 0.5;
+	assert_eq!(crossfade.levels(), (0.75, 0.25));
+	assert!(!crossfade.done());
+	crossfade.elapsed = 3.0;
+	assert_eq!(crossfade.levels(), (0.0, 1.0));
+	assert!(crossfade.done());
+	assert_eq!(Crossfade::new(None, 0.0).levels(), (0.0, 1.0));
+}
+
+/// Positional audio
+#[test]
+fn spatial() {
+	//* Rolloff curves */
+	let mut attenuation = Attenuation { rolloff: Rolloff::Linear, min_distance: 2.0, max_distance: 10.0 };
+	assert_eq!(attenuation.gain(1.0), 1.0);
+	assert_eq!(attenuation.gain(6.0), 0.5);
+	assert_eq!(attenuation.gain(12.0), 0.0);
+	attenuation.rolloff = Rolloff::Quadratic;
+	assert_eq!(attenuation.gain(6.0), 0.75);
+	attenuation.rolloff = Rolloff::Inverse;
+	assert!(attenuation.gain(4.0) < 0.5, "Inverse rolloff should fall off quickly up close.");
+	assert!(attenuation.gain(9.9) > 0.0);
+	assert_eq!(attenuation.gain(10.0), 0.0);
+	attenuation.rolloff = Rolloff::Custom(|fraction| if fraction < 0.5 { 1.0 } else { 0.0 });
+	assert_eq!(attenuation.gain(5.0), 1.0);
+	assert_eq!(attenuation.gain(7.0), 0.0);
+	
+	//* 2D listener at the camera target */
+	let mut camera = Camera { target: Vector3 { x: 100.0, y: 50.0, z: 0.0 }, ..Default::default() };
+	let listener = Listener::from_camera(&camera);
+	assert_eq!(listener.position, Vector3 { x: 100.0, y: 50.0, z: 0.0 });
+	let attenuation = Attenuation { rolloff: Rolloff::Linear, min_distance: 0.0, max_distance: 100.0 };
+	assert_eq!(listener.spatialize(Vector3 { x: 150.0, y: 50.0, z: 0.0 }, &attenuation), (0.5, 1.0));
+	assert_eq!(listener.spatialize(Vector3 { x: 75.0, y: 50.0, z: 0.0 }, &attenuation), (0.75, 0.0));
+	assert_eq!(listener.spatialize(Vector3 { x: 100.0, y: 0.0, z: 0.0 }, &attenuation), (0.5, 0.5));
+	assert_eq!(listener.spatialize(listener.position, &attenuation), (1.0, 0.5));
+	
+	//* 3D listener at the camera position, facing the target */
+	camera.camera_mode = CameraMode::Mode3D;
+	camera.target = Vector3 { x: 0.0, y: 0.0, z: 0.0 };
+	camera.distance = Vector3 { x: 0.0, y: 0.0, z: 10.0 };
+	let listener = Listener::from_camera(&camera);
+	assert_eq!(listener.position, Vector3 { x: 0.0, y: 0.0, z: 10.0 });
+	let (_, pan) = listener.spatialize(Vector3 { x: 10.0, y: 0.0, z: 10.0 }, &attenuation);
+	assert!((pan - 1.0).abs() < 1e-6);
+	let (volume, pan) = listener.spatialize(Vector3 { x: 0.0, y: 0.0, z: -40.0 }, &attenuation);
+	assert!((volume - 0.5).abs() < 1e-6);
+	assert!((pan - 0.5).abs() < 1e-6, "Sounds straight ahead should be centered.");
+	
+	//* Emitters */
+	let mut audio = AudioHandler::default();
+	audio.load_sfx("data/sounds/ugh.wav", "fire");
+	assert!(audio.add_emitter("missing", ZERO_3, true).is_none());
+	let fire = audio.add_emitter("fire", ZERO_3, true).unwrap();
+	let other = audio.add_emitter("fire", ZERO_3, false).unwrap();
+	assert_ne!(fire, other);
+	audio.move_emitter(fire, Vector3 { x: 5.0, y: 0.0, z: 0.0 });
+	assert_eq!(audio.emitter(fire).unwrap().position.x, 5.0);
+	assert!(audio.emitter(fire).unwrap().looping);
+	audio.remove_emitter(fire);
+	assert!(audio.emitter(fire).is_none());
+	assert!(audio.emitter(other).is_some());
+}
+
+/// Sfx voices
+#[test]
+fn sfx_voices() {
+	//* Free voices come first, then the policy picks one to steal */
+	let free = [(true, 1, 1.0), (false, 0, 1.0), (true, 3, 0.2)];
+	let busy = [(true, 4, 1.0), (true, 2, 0.5), (true, 3, 0.2)];
+	assert_eq!(StealPolicy::Oldest.choose(&free), Some(1));
+	assert_eq!(StealPolicy::Refuse.choose(&free), Some(1));
+	assert_eq!(StealPolicy::Oldest.choose(&busy), Some(1));
+	assert_eq!(StealPolicy::Quietest.choose(&busy), Some(2));
+	assert_eq!(StealPolicy::Refuse.choose(&busy), None);
+	assert_eq!(StealPolicy::Oldest.choose(&[]), None);
+	
+	//* Random variation stays within its range */
+	let mut sfx = Sfx::new(Default::default(), "sfx");
+	assert_eq!(sfx.next_variation(), (1.0, 1.0));
+	sfx.variation = Variation { pitch: 0.1, volume: 0.2 };
+	let variations: Vec<(f32, f32)> = (0..100).map(|_| sfx.next_variation()).collect();
+	assert!(variations.iter().all(|(pitch, volume)| (0.9..=1.1).contains(pitch) && (0.8..=1.0).contains(volume)));
+	assert!(variations.windows(2).any(|pair| pair[0] != pair[1]));
+	
+	let mut again = Sfx::new(Default::default(), "sfx");
+	again.variation = sfx.variation;
+	sfx.set_seed(42);
+	again.set_seed(42);
+	assert_eq!(sfx.next_variation(), again.next_variation(), "The same seed should vary the same way.");
+	
+	//* Voices through the handler, sounds that failed to load being skipped */
+	let mut audio = AudioHandler::default();
+	audio.load_sfx("missing.wav", "missing");
+	assert!(audio.sfx("missing").is_none());
+	let mut unloaded = Sfx::new(Default::default(), "sfx");
+	assert_eq!(unloaded.set_voices(SFX_VOICES).voices(), 1);
+	audio.load_sfx("data/sounds/ugh.wav", "gunshot");
+	assert_eq!(audio.sfx("gunshot").unwrap().voices(), SFX_VOICES);
+	audio.set_sfx_voices("gunshot", 8).set_sfx_policy("gunshot", StealPolicy::Quietest);
+	assert_eq!(audio.sfx("gunshot").unwrap().voices(), 8);
+	assert_eq!(audio.sfx("gunshot").unwrap().policy, StealPolicy::Quietest);
+	audio.set_sfx_voices("gunshot", 0);
+	assert_eq!(audio.sfx("gunshot").unwrap().voices(), 1);
+	assert_eq!(audio.sfx_mut("gunshot").unwrap().trigger(1.0, 1.0, 0.5), Some(0));
+}
+
+/// Effects
+#[test]
+fn effects() {
+	let run = |chain: &mut EffectChain, input: &[f32]| -> Vec<f32> {
+		let mut samples: Vec<f32> = input.iter().flat_map(|sample| [*sample, *sample]).collect();
+		chain.process(&mut samples);
+		samples.iter().step_by(2).copied().collect()
+	};
+	
+	//* Low-pass smooths out high frequencies, high-pass removes a constant offset */
+	let alternating: Vec<f32> = (0..1000).map(|i| if i % 2 == 0 { 1.0 } else { -1.0 }).collect();
+	let output = run(&mut EffectChain::new(vec![Effect::LowPass { cutoff: 500.0 }]), &alternating);
+	assert!(output[900..].iter().all(|sample| sample.abs() < 0.1));
+	let output = run(&mut EffectChain::new(vec![Effect::HighPass { cutoff: 500.0 }]), &[1.0; 1000]);
+	assert!(output[900..].iter().all(|sample| sample.abs() < 0.01));
+	
+	//* Echo comes back after the delay time, fed back at the feedback */
+	let mut impulse = vec![0.0; 600];
+	impulse[0] = 1.0;
+	let mut delay = EffectChain::with_sample_rate(vec![Effect::Delay { time: 0.25, feedback: 0.5, mix: 1.0 }], 1000);
+	let output = run(&mut delay, &impulse);
+	assert_eq!(output[0], 1.0);
+	assert!(output[1..250].iter().all(|sample| *sample == 0.0));
+	assert_eq!(output[250], 1.0);
+	assert_eq!(output[500], 0.5);
+	
+	//* Bitcrusher quantizes and holds samples */
+	let mut crusher = EffectChain::new(vec![Effect::Bitcrusher { bits: 2.0, downsample: 1.0 }]);
+	assert_eq!(run(&mut crusher, &[0.3, 0.1, -0.3]), vec![0.5, 0.0, -0.5]);
+	let ramp: Vec<f32> = (0..8).map(|i| i as f32 * 0.1).collect();
+	let mut crusher = EffectChain::new(vec![Effect::Bitcrusher { bits: 32.0, downsample: 4.0 }]);
+	assert_eq!(run(&mut crusher, &ramp), vec![0.0, 0.0, 0.0, 0.0, ramp[4], ramp[4], ramp[4], ramp[4]]);
+	
+	//* Limiter keeps peaks under the threshold and leaves quiet audio alone */
+	let sine: Vec<f32> = (0..1000).map(|i| (i as f32 * 0.05).sin()).collect();
+	let output = run(&mut EffectChain::new(vec![Effect::Limiter { threshold: 0.5, release: 0.1 }]), &sine);
+	assert!(output.iter().all(|sample| sample.abs() <= 0.5 + f32::EPSILON));
+	let quiet: Vec<f32> = sine.iter().map(|sample| sample * 0.2).collect();
+	assert_eq!(run(&mut EffectChain::new(vec![Effect::Limiter { threshold: 0.5, release: 0.1 }]), &quiet), quiet);
+	
+	//* Reverb tail is the same every time and stops once cleared */
+	let mut impulse = vec![0.0; 4000];
+	impulse[0] = 1.0;
+	let reverb = vec![Effect::Reverb { room_size: 0.8, damping: 0.5, mix: 1.0 }];
+	let mut chain = EffectChain::with_sample_rate(reverb.clone(), 44100);
+	let output = run(&mut chain, &impulse);
+	assert!(output[..1116].iter().all(|sample| *sample == 0.0));
+	assert!(output[1116..].iter().any(|sample| *sample != 0.0));
+	assert_eq!(output, run(&mut EffectChain::with_sample_rate(reverb, 44100), &impulse));
+	chain.clear();
+	assert!(run(&mut chain, &[0.0; 4000]).iter().all(|sample| *sample == 0.0));
+	
+	//* Parameters change and animate between effects of the same kind */
+	let open = Effect::LowPass { cutoff: 20000.0 };
+	let muffled = Effect::LowPass { cutoff: 500.0 };
+	assert_eq!(open.lerp(&muffled, 0.5), Some(Effect::LowPass { cutoff: 10250.0 }));
+	assert_eq!(open.lerp(&Effect::HighPass { cutoff: 500.0 }, 0.5), None);
+	let mut effects = Effects::default();
+	let id = effects.add(vec![Effect::Delay { time: 0.1, feedback: 0.2, mix: 0.3 }, open]);
+	effects.animate(id, 1, muffled, 1.0);
+	effects.animate(id, 0, muffled, 1.0);
+	effects.update(0.5);
+	assert_eq!(effects.chain(id).unwrap().effects()[1], Effect::LowPass { cutoff: 10250.0 });
+	assert!(effects.animating(id));
+	effects.update(0.5);
+	assert_eq!(effects.chain(id).unwrap().effects(), &[Effect::Delay { time: 0.1, feedback: 0.2, mix: 0.3 }, muffled]);
+	assert!(!effects.animating(id));
+	
+	//* Chains run on every stream of their target */
+	let mut audio = AudioHandler::default();
+	audio.load_sfx("data/sounds/ugh.wav", "gunshot");
+	let master = audio.add_effects(vec![open], Target::Master).unwrap();
+	let bus = audio.add_effects(vec![open], Target::Bus("sfx".to_string())).unwrap();
+	let music = audio.add_effects(vec![open], Target::Music).unwrap();
+	assert_eq!(audio.effects().attached(master), 1);
+	assert_eq!(audio.effects().attached(bus), SFX_VOICES);
+	assert_eq!(audio.effects().attached(music), 0);
+	audio.set_effect(master, 0, muffled).set_effect(master, 0, Effect::HighPass { cutoff: 200.0 });
+	assert_eq!(audio.effects().chain(master).unwrap().effects(), &[Effect::HighPass { cutoff: 200.0 }]);
+	assert_eq!(audio.effects().attached(master), 1, "Changing an effect's kind keeps the chain running.");
+	audio.set_sfx_voices("gunshot", 2);
+	assert_eq!(audio.effects().attached(bus), 2);
+	audio.detach_effects(bus);
+	assert_eq!(audio.effects().attached(bus), 0);
+	assert_eq!(audio.effects().target(bus), None);
+	audio.remove_effects(master);
+	assert_eq!(audio.effects().attached(master), 0);
+	assert!(audio.effects().chain(master).is_none());
+	
+	//* Chains only run on as many streams as there are free slots */
+	let full: Vec<EffectChainId> = (0..MAX_PROCESSORS).filter_map(|_| audio.add_effects(vec![open], Target::Master).ok()).collect();
+	assert_eq!(full.len(), MAX_PROCESSORS);
+	assert_eq!(audio.add_effects(vec![open], Target::Master).err(), Some(EffectError::OutOfSlots));
+	assert_eq!(audio.attach_effects_to(bus, Target::Bus("sfx".to_string())).err(), Some(EffectError::OutOfSlots));
+	assert_eq!((audio.effects().attached(bus), audio.effects().missing(bus)), (0, 2));
+	audio.remove_effects(full[0]);
+	assert_eq!((audio.effects().attached(bus), audio.effects().missing(bus)), (1, 1), "Freed slots go to chains left without one.");
+	for id in full { audio.remove_effects(id); }
+	audio.remove_effects(bus);
+	audio.remove_effects(music);
+}
+
+/// Offline rendering
+#[test]
+fn render() {
+	//* Two notes at 60 bpm, 4 ticks a second, the second one panned left at half volume */
+	let value = serde_json::json!({
+		"looping": true,
+		"bpm": 60,
+		"instruments": ["flat"],
+		"data": [ [[0, 2, 1.0, 0.5], 0], [[0, 2, 1.0, 0.0, 0.5], 4] ],
+	});
+	let midi = Midi::from_json(&value, |_| Some(Sound::default())).unwrap();
+	assert_eq!(midi.duration(), 1.25);
+	let flat = || vec![Some(Sample::from_samples(&[0.5; 2000], 1000, 1))];
+	
+	let mut renderer = Renderer::new(1000);
+	renderer.add_track(&midi, flat());
+	let song = renderer.render_song();
+	assert!(renderer.finished(), "Looping tracks should render once through.");
+	assert_eq!(song.len(), 3000);
+	assert_eq!(song[200..202], [0.5, 0.5]);
+	assert_eq!(song[1400..1402], [0.0, 0.0]);
+	assert_eq!(song[2400..2402], [0.25, 0.0]);
+	
+	//* Deterministic, and following the track's mixing */
+	let mut again = Renderer::new(1000);
+	again.add_track(&midi, flat());
+	assert_eq!(again.render_song(), song);
+	let mut quiet = midi.clone();
+	quiet.volume = 0.5;
+	quiet.pan = 1.0;
+	let mut renderer = Renderer::new(1000);
+	renderer.add_track(&quiet, flat());
+	assert_eq!(renderer.render(101)[200..202], [0.0, 0.25]);
+	let mut muted = midi.clone();
+	muted.muted = true;
+	let mut renderer = Renderer::new(1000);
+	renderer.add_track(&muted, flat()).add_track(&midi, vec![]);
+	assert!(renderer.render(2000).iter().all(|sample| *sample == 0.0));
+	
+	//* Pitch reads through the sample faster */
+	let ramp: Vec<f32> = (0..2000).map(|i| i as f32 / 2000.0).collect();
+	let mut high = midi.clone();
+	high.track[0].0.pitch = 2.0;
+	let mut renderer = Renderer::new(1000);
+	renderer.add_track(&high, vec![Some(Sample::from_samples(&ramp, 1000, 1))]);
+	assert_eq!(renderer.render(101)[200], 0.1);
+	
+	//* Synths render alongside samples, and tracks mix together */
+	let mut synth = value.clone();
+	synth["instruments"] = serde_json::json!([{ "waveform": "square", "attack": 0.0, "decay": 0.0, "sustain": 1.0, "release": 0.0, "volume": 1.0, "frequency": 125.0 }]);
+	let synth = Midi::from_json(&synth, |_| None).unwrap();
+	let mut renderer = Renderer::new(1000);
+	renderer.add_track(&synth, vec![]);
+	let square = renderer.render(8);
+	assert_eq!(square.iter().step_by(2).copied().collect::<Vec<f32>>(), vec![1.0, 1.0, 1.0, 1.0, -1.0, -1.0, -1.0, -1.0]);
+	renderer.volume = 0.25;
+	renderer.add_track(&midi, flat());
+	assert_eq!(renderer.render(1)[0], 0.25 * (1.0 + 0.5));
+	assert_eq!(renderer.tracks(), 2);
+	
+	//* Rendering what the handler's tracks would play */
+	let audio = AudioHandler::default();
+	assert_eq!(audio.render_tracks(44100, vec![]).tracks(), 0);
+	let wave = Renderer::new(1000).add_track(&midi, flat()).render_song_wave();
+	assert_eq!(wave.frame_count(), 1500);
+	assert_eq!(wave.sample_rate(), 1000);
+}
+
+/// Beats
+#[test]
+fn beats() {
+	let beat = |index: u64, bar: u64, beat: u32| MusicEvent::Beat { index, bar, beat };
+	
+	//* Events between two positions */
+	let map = BeatMap { start: 0.0, loop_start: 0.0, loop_end: 8.0, beats_per_bar: 4, markers: vec![("drop".to_string(), 4.5)] };
+	assert_eq!(map.events(0.0, 5.0), vec![
+		beat(1, 0, 1), beat(2, 0, 2), beat(3, 0, 3), MusicEvent::Bar(1), beat(4, 1, 0), MusicEvent::Marker("drop".to_string()), beat(5, 1, 1),
+	]);
+	
+	//* The clock passes each beat once, from the start of the song and around loops */
+	let mut clock = BeatClock::default();
+	assert_eq!(clock.advance(0.5, &map), vec![MusicEvent::Bar(0), beat(0, 0, 0)]);
+	assert_eq!(clock.advance(1.0, &map), vec![beat(1, 0, 1)]);
+	assert_eq!(clock.advance(1.5, &map), vec![]);
+	clock.sync(7.5);
+	assert_eq!(clock.advance(0.5, &map), vec![MusicEvent::Bar(0), beat(0, 0, 0)]);
+	assert_eq!(clock.position(), Some(0.5));
+	
+	//* Streamed songs with a tempo, starting half a second before their first beat */
+	let song = Song::new("song.ogg").with_tempo(120.0, 4, 0.5).with_marker("chorus", 2.5);
+	assert_eq!(song.beat(1.0), Some(1.0));
+	assert_eq!(Song::new("song.ogg").beat(1.0), None);
+	let map = song.clone().with_loop(2.5, Some(6.5)).beat_map(10.0).unwrap();
+	assert_eq!((map.start, map.loop_start, map.loop_end), (-1.0, 4.0, 12.0));
+	assert_eq!(map.markers, vec![("chorus".to_string(), 4.0)]);
+	let mut clock = BeatClock::default();
+	let map = song.beat_map(10.0).unwrap();
+	assert_eq!(clock.advance(song.beat(0.4).unwrap(), &map), vec![]);
+	assert_eq!(clock.advance(song.beat(0.5).unwrap(), &map), vec![MusicEvent::Bar(0), beat(0, 0, 0)]);
+	
+	//* Midi tracks count beats in ticks, with bars and markers from their JSON */
+	let value = serde_json::json!({
+		"looping": true,
+		"bpm": 60,
+		"beats_per_bar": 3,
+		"markers": [["drop", 8]],
+		"instruments": [],
+		"data": [ [[0, 4, 1.0, 0.5], 0], [[0, 1, 1.0, 0.5], 15] ],
+	});
+	let mut midi = Midi::from_json(&value, |_| None).unwrap();
+	assert_eq!(midi.beat_map(), BeatMap { start: 0.0, loop_start: 0.0, loop_end: 4.0, beats_per_bar: 3, markers: vec![("drop".to_string(), 2.0)] });
+	let mut clock = BeatClock::default();
+	let mut events = Vec::new();
+	for _ in 0..20 {
+		midi.advance(0.25);
+		events.extend(clock.advance(midi.beat(), &midi.beat_map()));
+	}
+	assert_eq!(events, vec![
+		MusicEvent::Bar(0), beat(0, 0, 0), beat(1, 0, 1), beat(2, 0, 2), MusicEvent::Marker("drop".to_string()), MusicEvent::Bar(1), beat(3, 1, 0),
+		MusicEvent::Bar(0), beat(0, 0, 0), beat(1, 0, 1),
+	]);
+	
+	//* Time signature and markers from a Standard MIDI File, 6/8 being 3 beats a bar */
+	let events = [0x00, 0xFF, 0x58, 0x04, 0x06, 0x03, 0x18, 0x08, 0x60, 0xFF, 0x06, 0x04, b'd', b'r', b'o', b'p', 0x00, 0xFF, 0x2F, 0x00];
+	let data = [
+		b"MThd".as_slice(), &6u32.to_be_bytes(), &0u16.to_be_bytes(), &1u16.to_be_bytes(), &96u16.to_be_bytes(),
+		b"MTrk", &(events.len() as u32).to_be_bytes(), &events,
+	].concat();
+	let smf = Midi::from_smf(&data, &[]).unwrap();
+	assert_eq!(smf.beats_per_bar, 3);
+	assert_eq!(smf.markers, vec![("drop".to_string(), 96)]);
+	
+	//* Handler follows the beat track in Midi mode */
+	let mut audio = AudioHandler::default();
+	assert!(audio.events().is_empty());
+	assert_eq!(audio.beat(), None);
+	*audio.track_mut(0) = Midi::from_json(&value, |_| None).unwrap();
+	audio.set_mode(AudioMode::Midi);
+	assert_eq!(audio.beat(), Some(0.0));
+	audio.set_beat_track(1);
+	assert_eq!((audio.beat_track(), audio.beat()), (1, None));
+}
+
+/// Standard MIDI File
+#[test]
+fn smf() {
+	let track = |events: &[u8]| [b"MTrk".as_slice(), &(events.len() as u32).to_be_bytes(), events].concat();
+	let header = |format: u16| [b"MThd".as_slice(), &6u32.to_be_bytes(), &format.to_be_bytes(), &2u16.to_be_bytes(), &96u16.to_be_bytes()].concat();
+	let tempo = track(&[
+		0x00, 0xFF, 0x51, 0x03, 0x07, 0xA1, 0x20,
+		0x81, 0x40, 0xFF, 0x51, 0x03, 0x0F, 0x42, 0x40,
+		0x00, 0xFF, 0x2F, 0x00,
+	]);
+	let notes = track(&[
+		0x00, 0xC0, 0x05,
+		0x00, 0xB0, 0x0A, 0x7F,
+		0x00, 0x90, 0x3C, 0x64,
+		0x60, 0x3C, 0x00,
+		0x00, 0x91, 0x48, 0x7F,
+		0x60, 0x81, 0x48, 0x00,
+		0x00, 0xFF, 0x2F, 0x00,
+	]);
+	let instruments = [
+		SmfInstrument { channel: Some(0), program: Some(5), root_note: 60, sound: Default::default(), synth: None },
+		SmfInstrument { channel: Some(1), program: None, root_note: 60, sound: Default::default(), synth: None },
+	];
+	
+	let midi = Midi::from_smf(&[header(1), tempo.clone(), notes.clone()].concat(), &instruments).unwrap();
+	assert_eq!(midi.ticks_per_beat, 96);
+	assert_eq!(midi.bpm, 120);
+	assert_eq!(midi.tempo, vec![(0, 500_000), (192, 1_000_000)]);
+	assert_eq!(midi.end, 192);
+	assert_eq!(midi.length, 2);
+	
+	let (first, tick) = &midi.track[0];
+	assert_eq!((first.id, first.duration, *tick), (0, 96, 0));
+	assert_eq!((first.pitch, first.pan), (1.0, 1.0));
+	assert_eq!(first.volume, 100.0 / 127.0);
+	let (second, tick) = &midi.track[1];
+	assert_eq!((second.id, second.duration, *tick), (1, 96, 96));
+	assert_eq!(second.pitch, 2.0);
+	
+	//* Unmapped programs are dropped */
+	let midi = Midi::from_smf(&[header(1), tempo.clone(), notes.clone()].concat(), &instruments[1..]).unwrap();
+	assert_eq!(midi.length, 1);
+	
+	//* Errors */
+	assert_eq!(Midi::from_smf(b"RIFF", &instruments).err(), Some(SmfError::NotSmf));
+	assert_eq!(Midi::from_smf(&[header(2), tempo.clone(), notes.clone()].concat(), &instruments).err(), Some(SmfError::UnsupportedFormat(2)));
+	assert_eq!(Midi::from_smf(&[header(1), tempo.clone(), notes[..12].to_vec()].concat(), &instruments).err(), Some(SmfError::Truncated));
+	let long = track(&[&[0x00, 0x90, 0x3C, 0x64][..], &[0xFF, 0xFF, 0xFF, 0x7F, 0x3C, 0x64].repeat(17)].concat());
+	assert_eq!(Midi::from_smf(&[header(0), long].concat(), &instruments).err(), Some(SmfError::TooLong), "Ticks overflowed.");
+	
+	//* Real-time messages between running status notes are skipped */
+	let realtime = track(&[
+		0x00, 0x91, 0x48, 0x7F,
+		0x00, 0xF8,
+		0x60, 0x48, 0x00,
+		0x00, 0xFF, 0x2F, 0x00,
+	]);
+	let midi = Midi::from_smf(&[header(0), realtime].concat(), &instruments).unwrap();
+	assert_eq!((midi.length, midi.track[0].0.duration), (1, 96));
+}
+
+/// Synth
+#[test]
+fn synth() {
+	//* Instruments from JSON */
+	let square = Synth::from_json(&serde_json::json!({ "waveform": "square", "attack": 0.0, "decay": 0.0, "sustain": 1.0, "release": 0.0, "volume": 1.0, "frequency": 128.0 })).unwrap();
+	assert_eq!(square.waveform, Waveform::Square);
+	assert_eq!(square.envelope, Envelope { attack: 0.0, decay: 0.0, sustain: 1.0, release: 0.0 });
+	let table = Synth::from_json(&serde_json::json!({ "waveform": [0.0, 1.0, 0.0, -1.0] })).unwrap();
+	assert_eq!(table.waveform, Waveform::Wavetable(vec![0.0, 1.0, 0.0, -1.0]));
+	assert_eq!(table.envelope, Envelope::default());
+	assert!(Synth::from_json(&serde_json::json!({ "waveform": "kazoo" })).is_none());
+	assert!(Synth::from_json(&serde_json::json!({})).is_none());
+	
+	//* Envelope */
+	let envelope = Envelope { attack: 1.0, decay: 1.0, sustain: 0.5, release: 2.0 };
+	assert_eq!(envelope.level(0.5, None), 0.5);
+	assert_eq!(envelope.level(1.5, None), 0.75);
+	assert_eq!(envelope.level(10.0, None), 0.5);
+	assert_eq!(envelope.level(11.0, Some(10.0)), 0.25);
+	assert_eq!(envelope.level(13.0, Some(10.0)), 0.0);
+	
+	//* Square wave, a cycle every 8 frames that stops after one cycle */
+	let mut synthesizer = Synthesizer::new(vec![Some(square.clone()), None]);
+	synthesizer.sample_rate = 1024;
+	synthesizer.note_on(0, 1.0, 1.0, 0.5, 8.0 / 1024.0);
+	synthesizer.note_on(1, 1.0, 1.0, 0.5, 1.0);
+	assert_eq!(synthesizer.active_voices(), 1, "Sample instruments shouldn't be played by the synthesizer.");
+	let samples = synthesizer.render(12);
+	let left: Vec<f32> = samples.iter().step_by(2).copied().collect();
+	assert_eq!(left, vec![1.0, 1.0, 1.0, 1.0, -1.0, -1.0, -1.0, -1.0, 0.0, 0.0, 0.0, 0.0]);
+	assert_eq!(samples.iter().skip(1).step_by(2).copied().collect::<Vec<f32>>(), left);
+	assert_eq!(synthesizer.active_voices(), 0);
+	
+	//* Panned fully left */
+	synthesizer.note_on(0, 1.0, 0.5, 0.0, 1.0);
+	let samples = synthesizer.render(4);
+	assert_eq!(samples, vec![0.5, 0.0, 0.5, 0.0, 0.5, 0.0, 0.5, 0.0]);
+	
+	//* Voice stealing */
+	let mut synthesizer = Synthesizer::new(vec![Some(square)]);
+	synthesizer.polyphony = 2;
+	for _ in 0..3 { synthesizer.note_on(0, 1.0, 1.0, 0.5, 1.0); }
+	assert_eq!(synthesizer.active_voices(), 2);
+	
+	//* Offline renders are deterministic */
+	let noise = Synth { waveform: Waveform::Noise, ..Default::default() };
+	let render = || {
+		let mut synthesizer = Synthesizer::new(vec![Some(noise.clone())]);
+		synthesizer.note_on(0, 2.0, 1.0, 0.5, 0.01);
+		synthesizer.render(1024)
+	};
+	let first = render();
+	assert_eq!(first, render());
+	assert!(first.iter().any(|sample| *sample > 0.0) && first.iter().any(|sample| *sample < 0.0));
+	assert!(first.iter().all(|sample| (-1.0..=1.0).contains(sample)));
+	
+	//* Midi tracks with synth instruments */
+	let value = serde_json::json!({
+		"bpm": 120,
+		"looping": false,
+		"instruments": [ "piano", { "waveform": "triangle" } ],
+		"data": [ [[0, 4, 1.0, 0.5], 0], [[1, 4, 1.0, 0.5], 4] ],
+	});
+	let mut midi = Midi::from_json(&value, |_| Some(Sound::load("data/sounds/ugh.wav"))).unwrap();
+	let synth = midi.synth.as_ref().unwrap();
+	assert!(!synth.plays(0));
+	assert!(synth.plays(1));
+	assert_eq!(synth.instruments[1].as_ref().unwrap().waveform, Waveform::Triangle);
+	midi.load_voices(4);
+	assert_eq!((midi.voices[0].len(), midi.voices[1].len()), (4, 0), "Synth instruments have no sound to alias.");
+	midi.unload_voices();
+	assert!(Midi::from_json(&serde_json::json!({ "bpm": 120, "looping": false, "instruments": [ "piano" ], "data": [ [[0, 4, 1.0, 0.5], 0] ] }), |_| Some(Default::default())).unwrap().synth.is_none());
+}
+
+/// Loader
+#[test]
+fn loader() {
+	let directory = std::env::temp_dir().join("pleroma_loader");
+	let _ = std::fs::create_dir_all(&directory);
+	let file = directory.join("a.json").to_string_lossy().to_string();
+	std::fs::write(&file, b"first").unwrap();
+	
+	let mut bulk = Bulk::new();
+	bulk.add_compressed("b", FileType::JSON, b"second".to_vec(), CompressionType::ZLib, CompressionLevel::Best);
+	let archive: std::sync::Arc<dyn Archive + Send + Sync> = std::sync::Arc::new(bulk);
+	
+	let mut loader = Loader::new();
+	assert_eq!(loader.progress(), 1.0);
+	loader
+		.queue_file("a", &file, AssetKind::Data)
+		.queue_archive("b", &archive, "b", AssetKind::Data)
+		.queue_file("missing", &directory.join("missing.json").to_string_lossy(), AssetKind::Data);
+	assert!(loader.progress() < 1.0);
+	
+	let start = std::time::Instant::now();
+	while !loader.done() && start.elapsed().as_secs() < 5 { loader.update(); }
+	assert_eq!(loader.progress(), 1.0);
+	assert!(matches!(loader.take("a"), Some(Asset::Data(data)) if data == b"first"));
+	assert!(matches!(loader.take("b"), Some(Asset::Data(data)) if data == b"second"));
+	assert_eq!(loader.take_failed(), vec!["missing".to_string()]);
+	
+	//* Progress restarts once everything finished */
+	loader.queue_file("a", &file, AssetKind::Data);
+	assert_eq!(loader.progress(), 0.0);
+	
+	//* Data that doesn't decode fails on the workers */
+	loader.queue_file("noise", &file, AssetKind::Sound);
+	let start = std::time::Instant::now();
+	while !loader.done() && start.elapsed().as_secs() < 5 { loader.update(); }
+	assert!(loader.take("noise").is_none());
+	assert_eq!(loader.take_failed(), vec!["noise".to_string()]);
+	
+	let _ = std::fs::remove_dir_all(directory);
+}
+
+/// Assets
+#[test]
+fn assets() {
+	let font = |size: i32| Font {
+		data: FontRl {
+			base_size: size,
+			glyph_count: 0,
+			glyph_padding: 0,
+			texture: TextureRl { id: 0, width: 0, height: 0, mipmaps: 0, format: 0 },
+			recs: std::ptr::null_mut(),
+			glyphs: std::ptr::null_mut(),
+		},
+		size: size as f32,
+		spacing: 1.0,
+		tint: BLACK,
+	};
+	
+	//* Deduplication and reference counts */
+	let mut assets = Assets::new();
+	let a = assets.insert("font", font(8));
+	let b = assets.get::<Font>("font").unwrap();
+	assert!(a == b);
+	assert_eq!(a.references(), 2);
+	assert_eq!(assets.count(), 1);
+	drop(b);
+	assert_eq!(a.references(), 1);
+	
+	//* Replacing keeps handles valid */
+	let c = assets.insert("font", font(16));
+	assert!(a == c);
+	assert_eq!(a.get().unwrap().data.base_size, 16);
+	
+	//* Dropping the last handle unloads */
+	drop(a);
+	drop(c);
+	assert_eq!(assets.count(), 0);
+	assert!(assets.get::<Font>("font").is_none());
+	
+	//* Unloading everything empties live handles */
+	let d = assets.insert("other", font(8));
+	assets.unload_all();
+	assert!(!d.is_loaded());
+	assert!(d.get().is_none());
+	assert_eq!(assets.count(), 0);
+	
+	//* Hot reload */
+	let filename = std::env::temp_dir().join("pleroma_hot_reload.txt").to_string_lossy().to_string();
+	std::fs::write(&filename, "8").unwrap();
+	let path = filename.clone();
+	let e = assets.insert("watched", font(8));
+	assets.watch(&e, &[&filename], move || Some(font(std::fs::read_to_string(&path).ok()?.parse().ok()?)));
+	assert!(assets.reload_changed().is_empty());
+	
+	let change = |contents: &str, seconds: u64| {
+		std::fs::write(&filename, contents).unwrap();
+		let file = std::fs::File::options().write(true).open(&filename).unwrap();
+		file.set_modified(std::time::SystemTime::now() + std::time::Duration::from_secs(seconds)).unwrap();
+	};
+	change("24", 10);
+	assert_eq!(assets.reload_changed(), vec![("watched".to_string(), true)]);
+	assert_eq!(e.get().unwrap().data.base_size, 24);
+	
+	change("broken", 20);
+	assert_eq!(assets.reload_changed(), vec![("watched".to_string(), false)]);
+	assert_eq!(e.get().unwrap().data.base_size, 24, "Failed reload should keep the previous resource.");
+	
+	//* Reloads wait while the resource is borrowed */
+	change("32", 30);
+	let borrowed = e.get();
+	assert!(assets.reload_changed().is_empty());
+	drop(borrowed);
+	assert_eq!(assets.reload_changed(), vec![("watched".to_string(), true)]);
+	assert_eq!(e.get().unwrap().data.base_size, 32);
+	
+	let _ = std::fs::remove_file(filename);
+}
+
+/// Keybinds
+#[test]
+fn keybinds() {
+	let mut defaults = std::collections::HashMap::new();
+	defaults.insert("jump".to_string(), Keybind { device: Device::Keyboard, keys: [0, KeyboardKey::Space.into()] });
+	defaults.insert("sprint".to_string(), Keybind { device: Device::Keyboard, keys: [KeyboardKey::LeftControl.into(), KeyboardKey::W.into()] });
+	defaults.insert("look".to_string(), Keybind { device: Device::Mouse, keys: [0, MouseButton::Xp.into()] });
+	defaults.insert("fire".to_string(), Keybind { device: Device::Gamepad(1), keys: [GamepadButton::LeftTrigger1.into(), GamepadButton::RightTrigger.into()] });
+	
+	//* Names instead of codes */
+	assert_eq!(KeyboardKey::from_name("leftcontrol"), Some(KeyboardKey::LeftControl));
+	assert_eq!(MouseButton::from_name("Wheeln"), Some(MouseButton::Wheeln));
+	assert_eq!(GamepadButton::from_name("RightFaceDown"), Some(GamepadButton::RightFaceDown));
+	assert_eq!(KeyboardKey::from_name("Nothing"), None);
+	let json = keybinds_to_json(&defaults);
+	assert_eq!(json["sprint"]["modifier"], "LeftControl");
+	assert_eq!(json["sprint"]["key"], "W");
+	assert_eq!(json["jump"]["modifier"], serde_json::Value::Null);
+	assert_eq!(json["look"]["key"], "Xp");
+	assert_eq!(json["fire"]["gamepad"], 1);
+	assert_eq!(json["fire"]["key"], "RightTrigger");
+	
+	//* Round trip */
+	assert_eq!(keybinds_from_json(&json, &std::collections::HashMap::new()), Some(defaults.clone()), "Keybinds changed when saved and loaded.");
+	
+	//* Older file missing an action, with a remapped one and a broken one */
+	let file = serde_json::json!({
+		"jump": { "device": "gamepad", "gamepad": 0, "modifier": null, "key": "rightfacedown" },
+		"sprint": { "device": "keyboard", "key": "NotAKey" },
+		"look": { "device": "mouse", "key": 9 },
+	});
+	let loaded = keybinds_from_json(&file, &defaults).unwrap();
+	assert_eq!(loaded["jump"], Keybind { device: Device::Gamepad(0), keys: [0, GamepadButton::RightFaceDown.into()] }, "Remapped keybind wasn't loaded.");
+	assert_eq!(loaded["sprint"], defaults["sprint"], "Unreadable keybind should keep its default.");
+	assert_eq!(loaded["look"].keys, [0, 9], "Key codes should load.");
+	assert_eq!(loaded["fire"], defaults["fire"], "Missing action should get its default.");
+	assert_eq!(keybinds_from_json(&serde_json::json!([1, 2]), &defaults), None);
+}
+
+/// Rebinding
+#[test]
+fn rebind() {
+	let jump = Keybind { device: Device::Keyboard, keys: [0, KeyboardKey::Space.into()] };
+	let fire = Keybind { device: Device::Mouse, keys: [0, MouseButton::Left.into()] };
+	let mut keybindings = std::collections::HashMap::new();
+	keybindings.insert("jump".to_string(), jump.clone());
+	keybindings.insert("fire".to_string(), fire.clone());
+	let mut rebinder = Rebinder::default();
+	
+	//* Nothing happens without a rebind */
+	assert_eq!(rebinder.capture(fire.clone(), &mut keybindings), &RebindStatus::Idle);
+	assert_eq!(keybindings["jump"], jump);
+	
+	//* Conflicts are rejected and it keeps listening */
+	rebinder.start("jump");
+	assert_eq!(rebinder.action(), Some("jump"));
+	assert_eq!(rebinder.capture(fire.clone(), &mut keybindings), &RebindStatus::Conflict { keybind: fire.clone(), actions: vec!["fire".to_string()] });
+	assert!(rebinder.listening(), "Rebind stopped after a conflict.");
+	assert_eq!(keybindings["jump"], jump, "Rejected keybind was written.");
+	
+	//* Rebinding to its own keybind isn't a conflict */
+	assert_eq!(rebinder.capture(jump.clone(), &mut keybindings), &RebindStatus::Bound { keybind: jump.clone(), conflicts: vec![] });
+	assert!(!rebinder.listening());
+	
+	//* Free input is written */
+	let sprint = Keybind { device: Device::Keyboard, keys: [KeyboardKey::LeftShift.into(), KeyboardKey::W.into()] };
+	rebinder.start("sprint");
+	rebinder.capture(sprint.clone(), &mut keybindings);
+	assert_eq!(keybindings["sprint"], sprint, "New action wasn't bound.");
+	
+	//* Swapping gives the other action the old keybind */
+	rebinder.conflicts = ConflictPolicy::Swap;
+	rebinder.start("jump");
+	assert_eq!(rebinder.capture(fire.clone(), &mut keybindings), &RebindStatus::Bound { keybind: fire.clone(), conflicts: vec!["fire".to_string()] });
+	assert_eq!(keybindings["jump"], fire);
+	assert_eq!(keybindings["fire"], jump, "Keybinds weren't swapped.");
+	
+	//* Allowing leaves both on the same input */
+	rebinder.conflicts = ConflictPolicy::Allow;
+	rebinder.start("sprint");
+	rebinder.capture(fire.clone(), &mut keybindings);
+	assert_eq!(keybindings["sprint"], fire);
+	assert_eq!(keybindings["jump"], fire);
+	
+	//* Cancelling keeps the old keybind */
+	rebinder.start("jump");
+	rebinder.cancel();
+	assert_eq!(rebinder.status(), &RebindStatus::Cancelled);
+	assert_eq!(rebinder.capture(jump.clone(), &mut keybindings), &RebindStatus::Cancelled);
+	assert_eq!(keybindings["jump"], fire, "Cancelled rebind was written.");
+	
+	assert!(KeyboardKey::RightAlt.is_modifier());
+	assert!(!KeyboardKey::A.is_modifier());
+}
+
 /// Color
 #[test]
 fn color() {